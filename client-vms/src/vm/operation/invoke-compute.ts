@@ -117,7 +117,7 @@ export class InvokeCompute implements Operation<Uuid> {
           valueIds,
           inputBindings,
           outputBindings,
-          values: nadaValuesToProto(share.shares.to_js_object()),
+          values: nadaValuesToProto(share.shares),
         }),
       });
     });
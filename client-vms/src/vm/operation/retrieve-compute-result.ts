@@ -1,6 +1,6 @@
 import { create } from "@bufbuild/protobuf";
 import { type Client, createClient } from "@connectrpc/connect";
-import { EncryptedNadaValues, PartyShares } from "@nillion/client-wasm";
+import { PartyShares } from "@nillion/client-wasm";
 import { Effect as E, pipe } from "effect";
 import type { UnknownException } from "effect/Cause";
 import { parse } from "uuid";
@@ -62,7 +62,9 @@ export class RetrieveComputeResult implements Operation<NadaValuesRecord> {
         onFailure: (e) =>
           E.sync(() => Log("Retrieve compute results failed: %O", e)),
         onSuccess: (data) =>
-          E.sync(() => Log("Retrieved compute results: %O", data)),
+          E.sync(() =>
+            Log("Retrieved compute results: %O", Object.keys(data)),
+          ),
       }),
       E.runPromise,
     );
@@ -102,8 +104,8 @@ export class RetrieveComputeResult implements Operation<NadaValuesRecord> {
           if (response.state.case === "success") {
             return new PartyShares(
               nodeId.toWasm(),
-              EncryptedNadaValues.from_js_object(
-                nadaValuesFromProto(response.state.value.values),
+              nadaValuesFromProto(
+                response.state.value.values,
                 this.config.vm.masker.modulo(),
               ),
             );
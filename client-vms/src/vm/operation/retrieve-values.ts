@@ -1,6 +1,6 @@
 import { create } from "@bufbuild/protobuf";
 import { type Client, createClient } from "@connectrpc/connect";
-import { EncryptedNadaValues, PartyShares } from "@nillion/client-wasm";
+import { PartyShares } from "@nillion/client-wasm";
 import { Effect as E, pipe } from "effect";
 import type { UnknownException } from "effect/Cause";
 import { parse as parseUuid } from "uuid";
@@ -60,7 +60,8 @@ export class RetrieveValues implements Operation<NadaValuesRecord> {
       E.catchAll(unwrapExceptionCause),
       E.tapBoth({
         onFailure: (e) => E.sync(() => Log("Retrieve values failed: %O", e)),
-        onSuccess: (data) => E.sync(() => Log("Retrieved values: %O", data)),
+        onSuccess: (data) =>
+          E.sync(() => Log("Retrieved values: %O", Object.keys(data))),
       }),
       E.runPromise,
     );
@@ -90,8 +91,8 @@ export class RetrieveValues implements Operation<NadaValuesRecord> {
         (response) =>
           new PartyShares(
             nodeId.toWasm(),
-            EncryptedNadaValues.from_js_object(
-              nadaValuesFromProto(response.values),
+            nadaValuesFromProto(
+              response.values,
               this.config.vm.masker.modulo(),
             ),
           ),
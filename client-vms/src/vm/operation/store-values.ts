@@ -106,7 +106,7 @@ export class StoreValues implements Operation<Uuid> {
           signedReceipt,
           permissions,
           updateIdentifier,
-          values: nadaValuesToProto(shares.shares.to_js_object()),
+          values: nadaValuesToProto(shares.shares),
         }),
       });
     });
@@ -1,3 +1,4 @@
+import { inspect } from "node:util";
 import {
   type EcdsaSignature,
   type EddsaSignature,
@@ -180,6 +181,19 @@ describe("Wasm compatability", () => {
     });
   });
 
+  describe("Redaction", () => {
+    it("redacts values when inspected", () => {
+      const value = NadaValue.new_secret_integer("42");
+      expect(inspect(value)).toEqual("SecretInteger(redacted)");
+
+      const values = new NadaValues();
+      values.insert("foo", value);
+      expect(inspect(values)).toEqual(
+        "NadaValues { foo: SecretInteger(redacted) }",
+      );
+    });
+  });
+
   describe("PartyId", () => {
     const expectedBase58 = "uuz3xgfhzJN0L88tlTHUqIiRYpaYejJbjeO26Qj1SCY=";
     it("can construct a party id from a uint array", () => {
@@ -0,0 +1,336 @@
+//! Cosmos SDK transaction signing helpers.
+use crate::{
+    ecdsa::{parse_public_key, public_key_from_value},
+    errors::{JsResult, ValueError},
+    values::{EcdsaSignature, NadaValue},
+};
+use prost::Message;
+use ripemd::Ripemd160;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// The characters used to encode 5 bit groups in bech32.
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// The generator of the BCH code used for bech32 checksums.
+const BECH32_GENERATOR: [u32; 5] = [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
+
+/// The `cosmos.tx.v1beta1.SignDoc` message signed in `SIGN_MODE_DIRECT`.
+#[derive(Clone, PartialEq, Message)]
+struct SignDoc {
+    /// The encoded `TxBody`.
+    #[prost(bytes = "vec", tag = "1")]
+    body_bytes: Vec<u8>,
+
+    /// The encoded `AuthInfo`.
+    #[prost(bytes = "vec", tag = "2")]
+    auth_info_bytes: Vec<u8>,
+
+    /// The chain the transaction is meant for.
+    #[prost(string, tag = "3")]
+    chain_id: String,
+
+    /// The account number of the signer.
+    #[prost(uint64, tag = "4")]
+    account_number: u64,
+}
+
+/// The `cosmos.tx.v1beta1.TxRaw` message broadcast to the chain.
+#[derive(Clone, PartialEq, Message)]
+struct TxRaw {
+    /// The encoded `TxBody`.
+    #[prost(bytes = "vec", tag = "1")]
+    body_bytes: Vec<u8>,
+
+    /// The encoded `AuthInfo`.
+    #[prost(bytes = "vec", tag = "2")]
+    auth_info_bytes: Vec<u8>,
+
+    /// One signature per signer, in the order of the signer infos in `AuthInfo`.
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    signatures: Vec<Vec<u8>>,
+}
+
+/// A Cosmos SDK `SignDoc`, the document signed for transactions in `SIGN_MODE_DIRECT`.
+#[wasm_bindgen]
+pub struct CosmosSignDoc {
+    /// The decoded sign document.
+    sign_doc: SignDoc,
+
+    /// The encoded sign document, which is what gets signed. Decoded documents keep their original bytes since
+    /// re-encoding them isn't guaranteed to produce the same ones.
+    bytes: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl CosmosSignDoc {
+    /// Construct a new sign document.
+    ///
+    /// @param {Uint8Array} body_bytes - The protobuf encoded `TxBody`
+    /// @param {Uint8Array} auth_info_bytes - The protobuf encoded `AuthInfo`
+    /// @param {string} chain_id - The chain the transaction is meant for
+    /// @param {bigint} account_number - The account number of the signer
+    /// @return {CosmosSignDoc} The sign document
+    ///
+    /// @example
+    /// const signDoc = new CosmosSignDoc(bodyBytes, authInfoBytes, "nillion-1", 42n);
+    #[wasm_bindgen(constructor, skip_jsdoc)]
+    pub fn new(body_bytes: Vec<u8>, auth_info_bytes: Vec<u8>, chain_id: String, account_number: u64) -> Self {
+        let sign_doc = SignDoc { body_bytes, auth_info_bytes, chain_id, account_number };
+        let bytes = sign_doc.encode_to_vec();
+        Self { sign_doc, bytes }
+    }
+
+    /// Decode a protobuf encoded sign document, such as the ones produced by cosmjs' `makeSignBytes`.
+    ///
+    /// @param {Uint8Array} sign_doc - The encoded `SignDoc`
+    /// @return {CosmosSignDoc} The sign document
+    /// @throws {Error} if the sign document is malformed.
+    ///
+    /// @example
+    /// const signDoc = CosmosSignDoc.from_bytes(makeSignBytes(doc));
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn from_bytes(sign_doc: &[u8]) -> JsResult<CosmosSignDoc> {
+        let bytes = sign_doc.to_vec();
+        let sign_doc =
+            SignDoc::decode(sign_doc).map_err(|e| ValueError::new_err(&format!("Invalid sign document: {e}")))?;
+        Ok(Self { sign_doc, bytes })
+    }
+
+    /// The protobuf encoding of this sign document, the bytes it was decoded from if it was decoded.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bytes.clone()
+    }
+
+    /// The SHA-256 hash of this sign document, as an `EcdsaDigestMessage` value.
+    ///
+    /// @return {NadaValue} The digest to sign
+    ///
+    /// @example
+    /// const digest = signDoc.to_digest_message();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_digest_message(&self) -> JsResult<NadaValue> {
+        NadaValue::new_ecdsa_digest_message(Sha256::digest(&self.bytes).to_vec())
+    }
+
+    /// Encode the signed transaction as a `TxRaw`, ready to be broadcast.
+    ///
+    /// The signature is normalized to have a low s component, as required by the Cosmos SDK.
+    ///
+    /// @param {EcdsaSignature} signature - The signature over this sign document's digest
+    /// @return {Uint8Array} The protobuf encoded `TxRaw`
+    /// @throws {Error} if the signature is malformed.
+    ///
+    /// @example
+    /// const txRaw = signDoc.to_signed_bytes(signature);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_signed_bytes(&self, signature: &EcdsaSignature) -> JsResult<Vec<u8>> {
+        let tx = TxRaw {
+            body_bytes: self.sign_doc.body_bytes.clone(),
+            auth_info_bytes: self.sign_doc.auth_info_bytes.clone(),
+            signatures: vec![signature.to_compact_bytes()?],
+        };
+        Ok(tx.encode_to_vec())
+    }
+}
+
+#[wasm_bindgen]
+impl NadaValue {
+    /// Create a new ecdsa digest message out of an Amino JSON sign document, as used by `SIGN_MODE_LEGACY_AMINO_JSON`.
+    ///
+    /// The document is serialized the same way cosmjs' `serializeSignDoc` does, with sorted keys, no whitespace and
+    /// `&`, `<` and `>` escaped, and then hashed using SHA-256.
+    ///
+    /// @param {string} sign_doc - The Amino JSON sign document
+    /// @return {NadaValue} The `EcdsaDigestMessage` value
+    /// @throws {Error} if the sign document is not valid JSON.
+    ///
+    /// @example
+    /// const digest = NadaValue.new_ecdsa_digest_message_from_amino_json(JSON.stringify(signDoc));
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn new_ecdsa_digest_message_from_amino_json(sign_doc: &str) -> JsResult<NadaValue> {
+        let sign_doc: Value =
+            serde_json::from_str(sign_doc).map_err(|e| ValueError::new_err(&format!("Invalid sign document: {e}")))?;
+        let serialized = serialize_amino_json(&sign_doc).map_err(|e| ValueError::new_err(&e))?;
+        NadaValue::new_ecdsa_digest_message(Sha256::digest(serialized.as_bytes()).to_vec())
+    }
+
+    /// Derive the bech32 Cosmos account address for an `EcdsaPublicKey` value.
+    ///
+    /// @param {string} prefix - The bech32 human readable prefix of the chain, e.g. `nillion` or `cosmos`
+    /// @return {string} The account address
+    /// @throws {Error} if the value is not a valid ecdsa public key or the prefix is invalid.
+    ///
+    /// @example
+    /// const address = publicKey.to_cosmos_address("nillion");
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_cosmos_address(&self, prefix: &str) -> JsResult<String> {
+        let public_key = parse_public_key(&public_key_from_value(self)?)?;
+        let hash = Ripemd160::digest(Sha256::digest(public_key.to_bytes(true)));
+        bech32_encode(prefix, &hash).map_err(|e| ValueError::new_err(&e))
+    }
+}
+
+/// Serialize JSON with sorted keys and no whitespace, escaping the characters cosmjs escapes.
+fn serialize_amino_json(value: &Value) -> Result<String, String> {
+    let mut output = String::new();
+    write_sorted_json(value, &mut output)?;
+    Ok(output.replace('&', "\\u0026").replace('<', "\\u003c").replace('>', "\\u003e"))
+}
+
+/// Write JSON with its object keys sorted.
+fn write_sorted_json(value: &Value, output: &mut String) -> Result<(), String> {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            output.push('{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                output.push_str(&to_json(key)?);
+                output.push(':');
+                write_sorted_json(value, output)?;
+            }
+            output.push('}');
+        }
+        Value::Array(items) => {
+            output.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_sorted_json(item, output)?;
+            }
+            output.push(']');
+        }
+        _ => output.push_str(&to_json(value)?),
+    }
+    Ok(())
+}
+
+/// Serialize a JSON leaf or key.
+fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|e| format!("Invalid sign document: {e}"))
+}
+
+/// Encode data as bech32 with the given human readable prefix.
+fn bech32_encode(prefix: &str, data: &[u8]) -> Result<String, String> {
+    if prefix.is_empty() || !prefix.bytes().all(|c| matches!(c, b'!'..=b'~') && !c.is_ascii_uppercase()) {
+        return Err(format!("Invalid bech32 prefix {prefix}"));
+    }
+    let mut values = to_5_bit_groups(data);
+    let checksum = bech32_checksum(prefix, &values);
+    values.extend(checksum);
+    let encoded: String =
+        values.iter().filter_map(|value| BECH32_CHARSET.get(usize::from(*value))).map(|c| char::from(*c)).collect();
+    Ok(format!("{prefix}1{encoded}"))
+}
+
+/// Regroup bytes into 5 bit groups, padding the last group with zeros.
+fn to_5_bit_groups(data: &[u8]) -> Vec<u8> {
+    let bits: Vec<u8> = data.iter().flat_map(|byte| (0..8).rev().map(move |bit| byte.wrapping_shr(bit) & 1)).collect();
+    bits.chunks(5)
+        .map(|chunk| chunk.iter().chain([0; 5].iter()).take(5).fold(0, |group: u8, bit| group.wrapping_shl(1) | bit))
+        .collect()
+}
+
+/// Compute the 6 character bech32 checksum of some 5 bit groups.
+fn bech32_checksum(prefix: &str, values: &[u8]) -> Vec<u8> {
+    let expanded_prefix = prefix.bytes().map(|c| c.wrapping_shr(5)).chain([0]).chain(prefix.bytes().map(|c| c & 31));
+    let polymod = bech32_polymod(expanded_prefix.chain(values.iter().copied()).chain([0; 6])) ^ 1;
+    (0..6u32)
+        .rev()
+        .map(|index| u8::try_from(polymod.wrapping_shr(index.wrapping_mul(5)) & 31).unwrap_or_default())
+        .collect()
+}
+
+/// The BCH checksum over some 5 bit groups.
+fn bech32_polymod(values: impl Iterator<Item = u8>) -> u32 {
+    values.fold(1, |checksum: u32, value| {
+        let top = checksum.wrapping_shr(25);
+        let checksum = (checksum & 0x1ff_ffff).wrapping_shl(5) ^ u32::from(value);
+        BECH32_GENERATOR
+            .iter()
+            .zip(0..)
+            .filter(|(_, bit)| top.wrapping_shr(*bit) & 1 == 1)
+            .fold(checksum, |checksum, (generator, _)| checksum ^ generator)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::keys::arithmetic::neg;
+    use nillion_client_core::generic_ec::{curves::Secp256k1, Scalar};
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn bech32() -> Result<(), JsValue> {
+        // Test vectors from BIP-173.
+        assert_eq!(bech32_encode("a", &[])?, "a12uel5l");
+        let data = hex::decode("00443214c74254b635cf84653a56d7c675be77df").unwrap_or_default();
+        assert_eq!(bech32_encode("abcdef", &data)?, "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw");
+        assert!(bech32_encode("", &data).is_err());
+        assert!(bech32_encode("Cosmos", &data).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn cosmos_address() -> Result<(), JsValue> {
+        // The example from cosmjs.
+        let public_key =
+            hex::decode("034f04181eeba35391b858633a765c4a0c189697b40d216354d50890d350c70290").unwrap_or_default();
+        let public_key = NadaValue::new_ecdsa_public_key(public_key)?;
+        assert_eq!(public_key.to_cosmos_address("cosmos")?, "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6");
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn amino_json() -> Result<(), JsValue> {
+        let sign_doc = r#"{"z": 1, "a": {"y": [1, "<&>"], "b": null}, "m": "x"}"#;
+        let serialized = r#"{"a":{"b":null,"y":[1,"<&>"]},"m":"x","z":1}"#;
+        let value: Value = serde_json::from_str(sign_doc).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        assert_eq!(serialize_amino_json(&value)?, serialized);
+
+        let digest = NadaValue::new_ecdsa_digest_message_from_amino_json(sign_doc)?;
+        assert_eq!(digest.to_byte_array()?.to_vec(), Sha256::digest(serialized).to_vec());
+        assert!(NadaValue::new_ecdsa_digest_message_from_amino_json("{").is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn sign_doc() -> Result<(), JsValue> {
+        let sign_doc = CosmosSignDoc::new(vec![1, 2, 3], vec![4, 5], "nillion-1".to_string(), 42);
+        let decoded = CosmosSignDoc::from_bytes(&sign_doc.to_bytes())?;
+        assert!(decoded.sign_doc == sign_doc.sign_doc);
+        let digest = sign_doc.to_digest_message()?;
+        assert_eq!(digest.to_byte_array()?.to_vec(), Sha256::digest(sign_doc.to_bytes()).to_vec());
+
+        // Decoded documents are hashed as given, even if prost would encode them differently, here with the account
+        // number before the chain id.
+        let mut reordered = vec![0x20, 42, 0x1a, 9];
+        reordered.extend_from_slice(b"nillion-1");
+        let decoded = CosmosSignDoc::from_bytes(&reordered)?;
+        assert_eq!(decoded.sign_doc.account_number, 42);
+        assert_eq!(decoded.to_bytes(), reordered);
+        let digest = decoded.to_digest_message()?;
+        assert_eq!(digest.to_byte_array()?.to_vec(), Sha256::digest(&reordered).to_vec());
+
+        // The signature is stored in its compact low-s form.
+        let r = [1; 32];
+        let s = Scalar::<Secp256k1>::one();
+        let signature = EcdsaSignature::new(r.to_vec(), neg(s).to_be_bytes().to_vec());
+        let tx = TxRaw::decode(sign_doc.to_signed_bytes(&signature)?.as_slice())
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let mut expected = r.to_vec();
+        expected.extend_from_slice(&s.to_be_bytes());
+        assert_eq!(tx.body_bytes, vec![1, 2, 3]);
+        assert_eq!(tx.auth_info_bytes, vec![4, 5]);
+        assert_eq!(tx.signatures, vec![expected]);
+        Ok(())
+    }
+}
@@ -0,0 +1,409 @@
+//! Ecdsa signature utilities.
+use crate::{
+    errors::{JsResult, ValueError},
+    keys::arithmetic::{add, add_points, mul, mul_generator, mul_point, neg, sub, sub_points},
+    values::{EcdsaSignature, NadaValue, PartyShares},
+};
+use nillion_client_core::generic_ec::{curves::Secp256k1, Point, Scalar};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen]
+impl EcdsaSignature {
+    /// Verify this signature against a public key and a message digest.
+    ///
+    /// @param {NadaValue} public_key - The `EcdsaPublicKey` value the signature should be valid for
+    /// @param {NadaValue} digest - The `EcdsaDigestMessage` value that was signed
+    /// @return {boolean} Whether the signature is valid
+    /// @throws {Error} if the values provided are not an ecdsa public key and digest, or the signature is malformed.
+    ///
+    /// @example
+    /// const valid = signature.verify(publicKey, digest);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn verify(&self, public_key: &NadaValue, digest: &NadaValue) -> JsResult<bool> {
+        let public_key = public_key_from_value(public_key)?;
+        let digest = digest_from_value(digest)?;
+        self.verify_bytes(&public_key, &digest)
+    }
+
+    /// Verify this signature against a public key and a message digest in binary format.
+    ///
+    /// @param {Uint8Array} public_key - The SEC1 encoded public key, either compressed or uncompressed
+    /// @param {Uint8Array} digest - The 32 byte digest that was signed
+    /// @return {boolean} Whether the signature is valid
+    /// @throws {Error} if the public key, the digest or the signature are malformed.
+    ///
+    /// @example
+    /// const valid = signature.verify_bytes(publicKey, digest);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn verify_bytes(&self, public_key: &[u8], digest: &[u8]) -> JsResult<bool> {
+        let public_key = parse_public_key(public_key)?;
+        let digest = parse_digest(digest)?;
+        let (r, s) = self.scalars()?;
+        Ok(verify_prehashed(&public_key, &digest, &r, &s))
+    }
+
+    /// Whether the s component of this signature is in the lower half of the group order, as required by EIP-2.
+    pub fn is_low_s(&self) -> JsResult<bool> {
+        let (_, s) = self.scalars()?;
+        Ok(is_low_s(&s))
+    }
+
+    /// Get the equivalent signature with a low s component, as required by EIP-2.
+    ///
+    /// @return {EcdsaSignature} This signature with s replaced by `n - s` if s is in the upper half of the group order
+    ///
+    /// @example
+    /// const normalized = signature.normalize_s();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn normalize_s(&self) -> JsResult<EcdsaSignature> {
+        let (r, s) = self.scalars()?;
+        let s = normalize_s(s);
+        Ok(EcdsaSignature::new(r.to_be_bytes().to_vec(), s.to_be_bytes().to_vec()))
+    }
+
+    /// Compute the recovery id of this signature.
+    ///
+    /// The recovery id is the parity of the y coordinate of the point used during signing. Recovery ids 2 and 3,
+    /// which only happen if that point's x coordinate is larger than the group order, are not supported.
+    ///
+    /// @param {NadaValue} public_key - The `EcdsaPublicKey` value the signature is valid for
+    /// @param {NadaValue} digest - The `EcdsaDigestMessage` value that was signed
+    /// @return {number} The recovery id, either 0 or 1
+    /// @throws {Error} if the signature is not valid for the public key and digest.
+    ///
+    /// @example
+    /// const recoveryId = signature.recovery_id(publicKey, digest);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn recovery_id(&self, public_key: &NadaValue, digest: &NadaValue) -> JsResult<u8> {
+        let public_key = parse_public_key(&public_key_from_value(public_key)?)?;
+        let digest = parse_digest(&digest_from_value(digest)?)?;
+        let (r, s) = self.scalars()?;
+        recovery_id(&public_key, &digest, &r, &s)
+    }
+
+    /// Serialize this signature in the 65 byte `r || s || v` format used by Ethereum.
+    ///
+    /// The signature is normalized to have a low s component and `v` is `27 + recovery id`.
+    ///
+    /// @param {NadaValue} public_key - The `EcdsaPublicKey` value the signature is valid for
+    /// @param {NadaValue} digest - The `EcdsaDigestMessage` value that was signed
+    /// @return {Uint8Array} The 65 byte signature
+    /// @throws {Error} if the signature is not valid for the public key and digest.
+    ///
+    /// @example
+    /// const bytes = signature.to_recoverable_bytes(publicKey, digest);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_recoverable_bytes(&self, public_key: &NadaValue, digest: &NadaValue) -> JsResult<Vec<u8>> {
+        let signature = self.normalize_s()?;
+        let recovery_id = signature.recovery_id(public_key, digest)?;
+        let mut bytes = signature.r.clone();
+        bytes.extend_from_slice(&signature.s);
+        bytes.push(recovery_id.saturating_add(27));
+        Ok(bytes)
+    }
+
+    /// Serialize this signature in the 64 byte compact `r || s` format used by Cosmos SDK and Bitcoin.
+    ///
+    /// The signature is normalized to have a low s component.
+    ///
+    /// @return {Uint8Array} The 64 byte signature
+    /// @throws {Error} if the signature is malformed.
+    ///
+    /// @example
+    /// const bytes = signature.to_compact_bytes();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_compact_bytes(&self) -> JsResult<Vec<u8>> {
+        let (r, s) = self.scalars()?;
+        let mut bytes = r.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&normalize_s(s).to_be_bytes());
+        Ok(bytes)
+    }
+
+    /// Combine the signature shares produced by a threshold subset of the nodes into the final signature.
+    ///
+    /// The shares are added together, the result is normalized to have a low s component and it is verified before
+    /// being returned.
+    ///
+    /// @param {Array<PartyShares>} shares - The shares of each party that took part in signing
+    /// @param {string} name - The name of the `EcdsaSignature` share within each party's shares
+    /// @param {NadaValue} public_key - The `EcdsaPublicKey` value the signature should be valid for
+    /// @param {NadaValue} digest - The `EcdsaDigestMessage` value that was signed
+    /// @return {EcdsaSignature} The combined signature
+    /// @throws {Error} if a share is missing or duplicated, the shares don't agree on r, or the combined signature is
+    ///   not valid for the public key and digest.
+    ///
+    /// @example
+    /// const signature = EcdsaSignature.combine_shares(partyShares, "signature", publicKey, digest);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn combine_shares(
+        shares: Vec<PartyShares>,
+        name: &str,
+        public_key: &NadaValue,
+        digest: &NadaValue,
+    ) -> JsResult<EcdsaSignature> {
+        let public_key = parse_public_key(&public_key_from_value(public_key)?)?;
+        let digest = parse_digest(&digest_from_value(digest)?)?;
+        let mut parties = Vec::new();
+        let mut r = None;
+        let mut s = Scalar::<Secp256k1>::zero();
+        for PartyShares { party, shares } in &shares {
+            if parties.contains(&&party.0) {
+                return Err(ValueError::new_err("signature shares contain the same party twice"));
+            }
+            parties.push(&party.0);
+            let share = match shares.0.get(name) {
+                Some(nillion_client_core::values::NadaValue::EcdsaSignature(share)) => share,
+                Some(_) => return Err(ValueError::new_err(&format!("value {name} is not an ecdsa signature share"))),
+                None => return Err(ValueError::new_err(&format!("value {name} not found in a party's shares"))),
+            };
+            match r {
+                Some(r) if r != &share.r => {
+                    return Err(ValueError::new_err("signature shares don't agree on r"));
+                }
+                Some(_) => {}
+                None => r = Some(&share.r),
+            }
+            s = add(s, share.sigma);
+        }
+        let r = *r.ok_or_else(|| ValueError::new_err("no signature shares provided"))?;
+        let s = normalize_s(s);
+        if !verify_prehashed(&public_key, &digest, &r, &s) {
+            return Err(ValueError::new_err("combined signature is not valid for the public key and digest"));
+        }
+        Ok(EcdsaSignature::new(r.to_be_bytes().to_vec(), s.to_be_bytes().to_vec()))
+    }
+}
+
+impl EcdsaSignature {
+    /// The r and s components as scalars.
+    pub(crate) fn scalars(&self) -> JsResult<(Scalar<Secp256k1>, Scalar<Secp256k1>)> {
+        let r = Scalar::from_be_bytes(&self.r)
+            .map_err(|_| ValueError::new_err("Ecdsa signature parameter r is larger than the group order"))?;
+        let s = Scalar::from_be_bytes(&self.s)
+            .map_err(|_| ValueError::new_err("Ecdsa signature parameter s is larger than the group order"))?;
+        Ok((r, s))
+    }
+}
+
+/// Get the public key contained in an `EcdsaPublicKey` value.
+pub(crate) fn public_key_from_value(value: &NadaValue) -> JsResult<Vec<u8>> {
+    match &value.0 {
+        nillion_client_core::values::NadaValue::EcdsaPublicKey(public_key) => Ok(public_key.0.to_vec()),
+        _ => Err(ValueError::new_err("value is not an ecdsa public key")),
+    }
+}
+
+/// Get the digest contained in an `EcdsaDigestMessage` value.
+pub(crate) fn digest_from_value(value: &NadaValue) -> JsResult<Vec<u8>> {
+    match &value.0 {
+        nillion_client_core::values::NadaValue::EcdsaDigestMessage(digest) => Ok(digest.to_vec()),
+        _ => Err(ValueError::new_err("value is not an ecdsa digest message")),
+    }
+}
+
+/// Parse a SEC1 encoded public key.
+pub(crate) fn parse_public_key(bytes: &[u8]) -> JsResult<Point<Secp256k1>> {
+    let point = Point::from_bytes(bytes).map_err(|_| ValueError::new_err("Invalid ecdsa public key"))?;
+    if point.is_zero() {
+        return Err(ValueError::new_err("Invalid ecdsa public key: point at infinity"));
+    }
+    Ok(point)
+}
+
+/// Parse a 32 byte message digest.
+pub(crate) fn parse_digest(bytes: &[u8]) -> JsResult<[u8; 32]> {
+    bytes.try_into().map_err(|_| ValueError::new_err("Message digest must be exactly 32 bytes long"))
+}
+
+/// Verify an ecdsa signature over a message digest, as described in SEC1 section 4.1.4.
+pub(crate) fn verify_prehashed(
+    public_key: &Point<Secp256k1>,
+    digest: &[u8; 32],
+    r: &Scalar<Secp256k1>,
+    s: &Scalar<Secp256k1>,
+) -> bool {
+    if r.is_zero() || public_key.is_zero() {
+        return false;
+    }
+    let Some(s_inv) = s.invert() else {
+        return false;
+    };
+    let z = Scalar::<Secp256k1>::from_be_bytes_mod_order(digest);
+    let point = add_points(mul_generator(mul(z, s_inv)), mul_point(*public_key, mul(*r, s_inv)));
+    match x_coordinate(&point) {
+        Some(x) => &x == r,
+        None => false,
+    }
+}
+
+/// Half the secp256k1 group order, rounded down, in big endian.
+const HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x5d, 0x57, 0x6e,
+    0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+/// Whether a scalar is in the lower half of the group order.
+pub(crate) fn is_low_s(s: &Scalar<Secp256k1>) -> bool {
+    // Both are fixed size big endian so comparing them lexicographically compares them numerically.
+    s.to_be_bytes().as_bytes() <= HALF_ORDER.as_slice()
+}
+
+/// Replace s by `n - s` if s is in the upper half of the group order.
+pub(crate) fn normalize_s(s: Scalar<Secp256k1>) -> Scalar<Secp256k1> {
+    if is_low_s(&s) { s } else { neg(s) }
+}
+
+/// Find the recovery id that recovers the given public key out of a signature.
+pub(crate) fn recovery_id(
+    public_key: &Point<Secp256k1>,
+    digest: &[u8; 32],
+    r: &Scalar<Secp256k1>,
+    s: &Scalar<Secp256k1>,
+) -> JsResult<u8> {
+    (0..2)
+        .find(|recovery_id| recover_public_key(digest, r, s, *recovery_id).as_ref() == Some(public_key))
+        .ok_or_else(|| ValueError::new_err("signature is not valid for the given public key and digest"))
+}
+
+/// Recover the public key that produced a signature, as described in SEC1 section 4.1.6.
+pub(crate) fn recover_public_key(
+    digest: &[u8; 32],
+    r: &Scalar<Secp256k1>,
+    s: &Scalar<Secp256k1>,
+    recovery_id: u8,
+) -> Option<Point<Secp256k1>> {
+    let r_inv = r.invert()?;
+    if s.is_zero() {
+        return None;
+    }
+    // The point used during signing has r as its x coordinate and the recovery id as its y coordinate's parity.
+    let mut encoded = vec![0x02 | (recovery_id & 1)];
+    encoded.extend_from_slice(&r.to_be_bytes());
+    let point = Point::<Secp256k1>::from_bytes(&encoded).ok()?;
+    let z = Scalar::<Secp256k1>::from_be_bytes_mod_order(digest);
+    let public_key = mul_point(sub_points(mul_point(point, *s), mul_generator(z)), r_inv);
+    if public_key.is_zero() { None } else { Some(public_key) }
+}
+
+/// The x coordinate of a point reduced modulo the group order.
+pub(crate) fn x_coordinate(point: &Point<Secp256k1>) -> Option<Scalar<Secp256k1>> {
+    if point.is_zero() {
+        return None;
+    }
+    let encoded = point.to_bytes(true);
+    let x = encoded.get(1..)?;
+    Some(Scalar::from_be_bytes_mod_order(x))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_test::*;
+
+    // A signature over a digest of `[3; 32]` with the private key `[7; 32]`.
+    const PUBLIC_KEY: [u8; 33] = [
+        2, 152, 156, 11, 118, 203, 86, 57, 113, 253, 201, 190, 243, 30, 192, 108, 53, 96, 243, 36, 157, 110, 233, 229,
+        216, 60, 87, 98, 85, 150, 224, 95, 111,
+    ];
+    const R: [u8; 32] = [
+        177, 196, 122, 222, 92, 46, 182, 179, 49, 147, 247, 44, 7, 130, 237, 137, 113, 114, 22, 202, 181, 37, 66, 170,
+        93, 215, 186, 43, 223, 68, 163, 239,
+    ];
+    const S: [u8; 32] = [
+        73, 38, 213, 173, 202, 139, 188, 38, 255, 17, 166, 85, 116, 76, 128, 103, 120, 30, 185, 10, 255, 107, 22, 161,
+        253, 123, 104, 198, 178, 71, 217, 145,
+    ];
+
+    #[wasm_bindgen_test]
+    fn verify() -> Result<(), JsValue> {
+        let signature = EcdsaSignature::new(R.to_vec(), S.to_vec());
+        let public_key = NadaValue::new_ecdsa_public_key(PUBLIC_KEY.to_vec())?;
+        let digest = NadaValue::new_ecdsa_digest_message(vec![3; 32])?;
+        assert!(signature.verify(&public_key, &digest)?);
+
+        let other_digest = NadaValue::new_ecdsa_digest_message(vec![4; 32])?;
+        assert!(!signature.verify(&public_key, &other_digest)?);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn verify_invalid_inputs() -> Result<(), JsValue> {
+        let signature = EcdsaSignature::new(R.to_vec(), S.to_vec());
+        assert!(signature.verify_bytes(&PUBLIC_KEY, &[3; 31]).is_err());
+        assert!(signature.verify_bytes(&[1; 33], &[3; 32]).is_err());
+
+        let digest = NadaValue::new_ecdsa_digest_message(vec![3; 32])?;
+        assert!(signature.verify(&digest, &digest).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn recoverable_signature() -> Result<(), JsValue> {
+        let public_key = NadaValue::new_ecdsa_public_key(PUBLIC_KEY.to_vec())?;
+        let digest = NadaValue::new_ecdsa_digest_message(vec![3; 32])?;
+        let signature = EcdsaSignature::new(R.to_vec(), S.to_vec());
+        assert!(signature.is_low_s()?);
+        assert_eq!(signature.recovery_id(&public_key, &digest)?, 0);
+
+        // Negating s yields an equally valid signature using the opposite recovery id.
+        let (r, s) = signature.scalars()?;
+        let high_s = EcdsaSignature::new(r.to_be_bytes().to_vec(), neg(s).to_be_bytes().to_vec());
+        assert!(!high_s.is_low_s()?);
+        assert!(high_s.verify(&public_key, &digest)?);
+        assert_eq!(high_s.recovery_id(&public_key, &digest)?, 1);
+        assert_eq!(high_s.normalize_s()?.s, S.to_vec());
+
+        let mut expected = R.to_vec();
+        expected.extend_from_slice(&S);
+        assert_eq!(high_s.to_compact_bytes()?, expected);
+        expected.push(27);
+        assert_eq!(high_s.to_recoverable_bytes(&public_key, &digest)?, expected);
+
+        let other_digest = NadaValue::new_ecdsa_digest_message(vec![4; 32])?;
+        assert!(signature.recovery_id(&public_key, &other_digest).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn combine_shares() -> Result<(), JsValue> {
+        use crate::values::{EncryptedNadaValues, PartyId};
+        use nillion_client_core::signature::EcdsaSignatureShare;
+
+        let party_shares =
+            |party: u8, r: Scalar<Secp256k1>, sigma: Scalar<Secp256k1>| -> Result<PartyShares, JsValue> {
+                let share =
+                    nillion_client_core::values::NadaValue::new_ecdsa_signature(EcdsaSignatureShare { r, sigma });
+                let shares = EncryptedNadaValues([("signature".to_string(), share)].into_iter().collect());
+                Ok(PartyShares::new(PartyId::new(vec![party]), shares)?)
+            };
+        let public_key = NadaValue::new_ecdsa_public_key(PUBLIC_KEY.to_vec())?;
+        let digest = NadaValue::new_ecdsa_digest_message(vec![3; 32])?;
+        let r = Scalar::<Secp256k1>::from_be_bytes(R).map_err(|_| "invalid r")?;
+        let s = Scalar::<Secp256k1>::from_be_bytes(S).map_err(|_| "invalid s")?;
+        let (first, second) = (Scalar::from(5u64), Scalar::from(11u64));
+
+        // The shares add up to the high s form of the signature, which gets normalized.
+        let shares = || -> Result<Vec<PartyShares>, JsValue> {
+            Ok(vec![
+                party_shares(1, r, first)?,
+                party_shares(2, r, second)?,
+                party_shares(3, r, sub(sub(neg(s), first), second))?,
+            ])
+        };
+        let signature = EcdsaSignature::combine_shares(shares()?, "signature", &public_key, &digest)?;
+        assert_eq!(signature.r, R.to_vec());
+        assert_eq!(signature.s, S.to_vec());
+
+        let other_digest = NadaValue::new_ecdsa_digest_message(vec![4; 32])?;
+        assert!(EcdsaSignature::combine_shares(shares()?, "signature", &public_key, &other_digest).is_err());
+        assert!(EcdsaSignature::combine_shares(shares()?, "other", &public_key, &digest).is_err());
+        assert!(EcdsaSignature::combine_shares(vec![], "signature", &public_key, &digest).is_err());
+
+        let mismatched = vec![party_shares(1, r, first)?, party_shares(2, Scalar::one(), -s - first)?];
+        assert!(EcdsaSignature::combine_shares(mismatched, "signature", &public_key, &digest).is_err());
+        let duplicated = vec![party_shares(1, r, first)?, party_shares(1, r, -s - first)?];
+        assert!(EcdsaSignature::combine_shares(duplicated, "signature", &public_key, &digest).is_err());
+        Ok(())
+    }
+}
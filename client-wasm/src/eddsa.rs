@@ -0,0 +1,153 @@
+//! Eddsa signature utilities.
+use crate::{
+    errors::{JsResult, ValueError},
+    values::{EddsaSignature, NadaValue},
+};
+use ed25519_dalek::{Signature, VerifyingKey};
+use nillion_client_core::generic_ec::{curves::Ed25519, Point};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen]
+impl EddsaSignature {
+    /// Verify this signature against a public key and a message.
+    ///
+    /// This follows the strict RFC 8032 semantics: non-canonical encodings of the public key, of r and of z are
+    /// rejected, as are small order public keys and r components.
+    ///
+    /// @param {NadaValue} public_key - The `EddsaPublicKey` value the signature should be valid for
+    /// @param {NadaValue} message - The `EddsaMessage` value that was signed
+    /// @return {boolean} Whether the signature is valid
+    /// @throws {Error} if the values provided are not an eddsa public key and message, or the signature is malformed.
+    ///
+    /// @example
+    /// const valid = signature.verify(publicKey, message);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn verify(&self, public_key: &NadaValue, message: &NadaValue) -> JsResult<bool> {
+        let public_key = public_key_from_value(public_key)?;
+        let message = message_from_value(message)?;
+        self.verify_bytes(&public_key, &message)
+    }
+
+    /// Verify this signature against a public key and a message in binary format.
+    ///
+    /// @param {Uint8Array} public_key - The 32 byte public key
+    /// @param {Uint8Array} message - The message that was signed
+    /// @return {boolean} Whether the signature is valid
+    /// @throws {Error} if the public key or the signature are malformed.
+    ///
+    /// @example
+    /// const valid = signature.verify_bytes(publicKey, message);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn verify_bytes(&self, public_key: &[u8], message: &[u8]) -> JsResult<bool> {
+        let public_key: [u8; 32] =
+            public_key.try_into().map_err(|_| ValueError::new_err("Public key must be exactly 32 bytes long"))?;
+        let signature = self.to_bytes()?;
+        Ok(verify_strict(&public_key, message, &signature))
+    }
+}
+
+impl EddsaSignature {
+    /// The signature as `r || z`.
+    pub(crate) fn to_bytes(&self) -> JsResult<[u8; 64]> {
+        let r: [u8; 32] =
+            self.r.as_slice().try_into().map_err(|_| ValueError::new_err("Eddsa signature r must be 32 bytes long"))?;
+        let z: [u8; 32] =
+            self.z.as_slice().try_into().map_err(|_| ValueError::new_err("Eddsa signature z must be 32 bytes long"))?;
+        let mut signature = [0; 64];
+        let (signature_r, signature_z) = signature.split_at_mut(32);
+        signature_r.copy_from_slice(&r);
+        signature_z.copy_from_slice(&z);
+        Ok(signature)
+    }
+}
+
+/// Get the public key contained in an `EddsaPublicKey` value.
+pub(crate) fn public_key_from_value(value: &NadaValue) -> JsResult<[u8; 32]> {
+    match &value.0 {
+        nillion_client_core::values::NadaValue::EddsaPublicKey(public_key) => Ok(*public_key),
+        _ => Err(ValueError::new_err("value is not an eddsa public key")),
+    }
+}
+
+/// Get the message contained in an `EddsaMessage` value.
+pub(crate) fn message_from_value(value: &NadaValue) -> JsResult<Vec<u8>> {
+    match &value.0 {
+        nillion_client_core::values::NadaValue::EddsaMessage(message) => Ok(message.to_vec()),
+        _ => Err(ValueError::new_err("value is not an eddsa message")),
+    }
+}
+
+/// Verify an ed25519 signature using the strict RFC 8032 rules.
+pub(crate) fn verify_strict(public_key: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+    let (r, _) = signature.split_at(32);
+    // `verify_strict` already rejects non-canonical z values and small order points, but it accepts
+    // non-canonical encodings of the public key.
+    if !is_canonical_point(public_key) || !is_canonical_point(r) {
+        return false;
+    }
+    let Ok(public_key) = VerifyingKey::from_bytes(public_key) else {
+        return false;
+    };
+    let signature = Signature::from_bytes(signature);
+    public_key.verify_strict(message, &signature).is_ok()
+}
+
+/// Whether the bytes are the canonical encoding of a curve point.
+fn is_canonical_point(bytes: &[u8]) -> bool {
+    match Point::<Ed25519>::from_bytes(bytes) {
+        Ok(point) => point.to_bytes(true).as_bytes() == bytes,
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_test::*;
+
+    // RFC 8032 section 7.1, test 2.
+    const PUBLIC_KEY: [u8; 32] = [
+        61, 64, 23, 195, 232, 67, 137, 90, 146, 183, 10, 167, 77, 27, 126, 188, 156, 152, 44, 207, 46, 196, 150, 140,
+        192, 205, 85, 241, 42, 244, 102, 12,
+    ];
+    const R: [u8; 32] = [
+        146, 160, 9, 169, 240, 212, 202, 184, 114, 14, 130, 11, 95, 100, 37, 64, 162, 178, 123, 84, 22, 80, 63, 143,
+        179, 118, 34, 35, 235, 219, 105, 218,
+    ];
+    const Z: [u8; 32] = [
+        8, 90, 193, 228, 62, 21, 153, 110, 69, 143, 54, 19, 208, 241, 29, 140, 56, 123, 46, 174, 180, 48, 42, 238, 176,
+        13, 41, 22, 18, 187, 12, 0,
+    ];
+    const MESSAGE: [u8; 1] = [0x72];
+
+    #[wasm_bindgen_test]
+    fn verify() -> Result<(), JsValue> {
+        let signature = EddsaSignature::new(R.to_vec(), Z.to_vec());
+        let public_key = NadaValue::new_eddsa_public_key(PUBLIC_KEY.to_vec())?;
+        let message = NadaValue::new_eddsa_message(MESSAGE.to_vec())?;
+        assert!(signature.verify(&public_key, &message)?);
+
+        let other_message = NadaValue::new_eddsa_message(vec![0x73])?;
+        assert!(!signature.verify(&public_key, &other_message)?);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn verify_non_canonical_z() -> Result<(), JsValue> {
+        // z + l, where l is the group order.
+        let z = vec![
+            245, 45, 183, 65, 89, 120, 171, 198, 27, 44, 46, 182, 174, 235, 252, 160, 56, 123, 46, 174, 180, 48, 42,
+            238, 176, 13, 41, 22, 18, 187, 12, 16,
+        ];
+        let signature = EddsaSignature::new(R.to_vec(), z);
+        assert!(!signature.verify_bytes(&PUBLIC_KEY, &MESSAGE)?);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn verify_malformed() {
+        let signature = EddsaSignature::new(R.to_vec(), Z[..31].to_vec());
+        assert!(signature.verify_bytes(&PUBLIC_KEY, &MESSAGE).is_err());
+    }
+}
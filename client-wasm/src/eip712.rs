@@ -0,0 +1,320 @@
+//! EIP-712 typed structured data hashing.
+use crate::errors::{JsResult, ValueError};
+use nillion_client_core::values::BigInt;
+use serde::Deserialize;
+use serde_json::Value;
+use sha3::{Digest, Keccak256};
+use std::collections::{BTreeSet, HashMap};
+
+/// The name of the type describing the signing domain.
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// The fields allowed in a signing domain, in the order the EIP-712 spec lists them.
+const DOMAIN_FIELDS: [(&str, &str); 5] = [
+    ("name", "string"),
+    ("version", "string"),
+    ("chainId", "uint256"),
+    ("verifyingContract", "address"),
+    ("salt", "bytes32"),
+];
+
+type Types = HashMap<String, Vec<TypeMember>>;
+
+/// Typed data, in the format accepted by `eth_signTypedData_v4`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypedData {
+    #[serde(default)]
+    types: Types,
+    primary_type: String,
+    domain: Value,
+    #[serde(default)]
+    message: Value,
+}
+
+/// A member of a struct type.
+#[derive(Deserialize)]
+struct TypeMember {
+    name: String,
+    #[serde(rename = "type")]
+    type_name: String,
+}
+
+/// Compute the digest to be signed for typed data encoded as JSON.
+///
+/// If the typed data doesn't define the `EIP712Domain` type, it is inferred from the fields present in the domain.
+pub(crate) fn hash_typed_data(json: &str) -> JsResult<[u8; 32]> {
+    let typed_data: TypedData =
+        serde_json::from_str(json).map_err(|e| ValueError::new_err(&format!("Invalid typed data: {e}")))?;
+    let mut types = typed_data.types;
+    if !types.contains_key(DOMAIN_TYPE) {
+        types.insert(DOMAIN_TYPE.to_string(), domain_type(&typed_data.domain));
+    }
+    let mut hasher = Keccak256::new();
+    hasher.update([0x19, 0x01]);
+    hasher.update(hash_struct(&types, DOMAIN_TYPE, &typed_data.domain).map_err(|e| ValueError::new_err(&e))?);
+    // Signing the domain by itself only hashes the domain separator.
+    if typed_data.primary_type != DOMAIN_TYPE {
+        let message =
+            hash_struct(&types, &typed_data.primary_type, &typed_data.message).map_err(|e| ValueError::new_err(&e))?;
+        hasher.update(message);
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// The domain type made out of the fields present in a domain.
+fn domain_type(domain: &Value) -> Vec<TypeMember> {
+    DOMAIN_FIELDS
+        .into_iter()
+        .filter(|(name, _)| domain.get(name).is_some())
+        .map(|(name, type_name)| TypeMember { name: name.to_string(), type_name: type_name.to_string() })
+        .collect()
+}
+
+/// Hash a struct as `keccak256(typeHash || encodeData(value))`.
+fn hash_struct(types: &Types, name: &str, value: &Value) -> Result<[u8; 32], String> {
+    let members = types.get(name).ok_or_else(|| format!("Unknown type {name}"))?;
+    let object = value.as_object().ok_or_else(|| format!("Value of type {name} must be an object"))?;
+    let mut encoded = keccak(encode_type(types, name)?.as_bytes()).to_vec();
+    for member in members {
+        let field = object.get(&member.name).ok_or_else(|| format!("Missing field {} in {name}", member.name))?;
+        let field = encode_field(types, &member.type_name, field)
+            .map_err(|e| format!("Invalid field {} in {name}: {e}", member.name))?;
+        encoded.extend_from_slice(&field);
+    }
+    Ok(keccak(&encoded))
+}
+
+/// Encode a type as its own definition followed by the definitions of every type it references, sorted by name.
+fn encode_type(types: &Types, name: &str) -> Result<String, String> {
+    let mut dependencies = BTreeSet::new();
+    collect_dependencies(types, name, &mut dependencies);
+    dependencies.remove(name);
+    std::iter::once(name)
+        .chain(dependencies)
+        .map(|dependency| {
+            let members = types.get(dependency).ok_or_else(|| format!("Unknown type {dependency}"))?;
+            let members: Vec<_> =
+                members.iter().map(|member| format!("{} {}", member.type_name, member.name)).collect();
+            Ok(format!("{dependency}({})", members.join(",")))
+        })
+        .collect()
+}
+
+/// Collect the struct types a type references, including itself.
+fn collect_dependencies<'a>(types: &'a Types, name: &'a str, dependencies: &mut BTreeSet<&'a str>) {
+    if dependencies.contains(name) {
+        return;
+    }
+    let Some(members) = types.get(name) else {
+        return;
+    };
+    dependencies.insert(name);
+    for member in members {
+        let base_type = member.type_name.split('[').next().unwrap_or(&member.type_name);
+        collect_dependencies(types, base_type, dependencies);
+    }
+}
+
+/// Encode a single field as 32 bytes.
+fn encode_field(types: &Types, type_name: &str, value: &Value) -> Result<[u8; 32], String> {
+    if let Some((element_type, length)) = array_element_type(type_name)? {
+        let items = value.as_array().ok_or_else(|| format!("{type_name} must be an array"))?;
+        if length.is_some_and(|length| length != items.len()) {
+            return Err(format!("{type_name} has {} elements", items.len()));
+        }
+        let mut encoded = Vec::new();
+        for item in items {
+            encoded.extend_from_slice(&encode_field(types, element_type, item)?);
+        }
+        return Ok(keccak(&encoded));
+    }
+    if types.contains_key(type_name) {
+        return hash_struct(types, type_name, value);
+    }
+    match type_name {
+        "string" => Ok(keccak(as_str(value)?.as_bytes())),
+        "bytes" => Ok(keccak(&decode_hex(as_str(value)?)?)),
+        "bool" => {
+            let value = value.as_bool().ok_or_else(|| "bool must be a boolean".to_string())?;
+            left_pad(&[u8::from(value)], 0)
+        }
+        "address" => {
+            let address = decode_hex(as_str(value)?)?;
+            if address.len() != 20 {
+                return Err("address must be 20 bytes long".to_string());
+            }
+            left_pad(&address, 0)
+        }
+        _ => {
+            if let Some(size) = type_name.strip_prefix("bytes") {
+                let size = parse_size(type_name, size, 1, 32)?;
+                let bytes = decode_hex(as_str(value)?)?;
+                if bytes.len() != size {
+                    return Err(format!("{type_name} must be {size} bytes long"));
+                }
+                let mut padded = [0; 32];
+                for (output, byte) in padded.iter_mut().zip(bytes) {
+                    *output = byte;
+                }
+                Ok(padded)
+            } else if let Some(bits) = type_name.strip_prefix("uint") {
+                encode_integer(value, parse_size(type_name, bits, 8, 256)?, false)
+            } else if let Some(bits) = type_name.strip_prefix("int") {
+                encode_integer(value, parse_size(type_name, bits, 8, 256)?, true)
+            } else {
+                Err(format!("Unknown type {type_name}"))
+            }
+        }
+    }
+}
+
+/// Split an array type into its element type and its length, if it is fixed.
+fn array_element_type(type_name: &str) -> Result<Option<(&str, Option<usize>)>, String> {
+    let Some((element_type, length)) = type_name.strip_suffix(']').and_then(|inner| inner.rsplit_once('[')) else {
+        return Ok(None);
+    };
+    if length.is_empty() {
+        return Ok(Some((element_type, None)));
+    }
+    let length = length.parse().map_err(|_| format!("Invalid array type {type_name}"))?;
+    Ok(Some((element_type, Some(length))))
+}
+
+/// Parse the size suffix of a sized type, which must be a multiple of `step` up to `max`.
+fn parse_size(type_name: &str, size: &str, step: usize, max: usize) -> Result<usize, String> {
+    let size: usize = size.parse().map_err(|_| format!("Unknown type {type_name}"))?;
+    if size == 0 || size > max || size % step != 0 {
+        return Err(format!("Unknown type {type_name}"));
+    }
+    Ok(size)
+}
+
+/// Encode an integer as a 32 byte two's complement big endian number.
+fn encode_integer(value: &Value, bits: usize, signed: bool) -> Result<[u8; 32], String> {
+    let text = match value {
+        Value::Number(number) => number.to_string(),
+        Value::String(text) => text.clone(),
+        _ => return Err("integer must be a number or a string".to_string()),
+    };
+    let integer = match text.strip_prefix("0x") {
+        Some(hex) => BigInt::parse_bytes(hex.as_bytes(), 16),
+        None => text.parse().ok(),
+    };
+    let integer: BigInt = integer.ok_or_else(|| format!("Invalid integer {text}"))?;
+    let negative = integer < BigInt::from(0);
+    let bytes = match (signed, negative) {
+        (true, _) => integer.to_signed_bytes_be(),
+        (false, false) => integer.magnitude().to_bytes_be(),
+        (false, true) => return Err(format!("Unsigned integer {text} is negative")),
+    };
+    if bytes.len() > bits / 8 {
+        return Err(format!("Integer {text} doesn't fit in {bits} bits"));
+    }
+    left_pad(&bytes, if negative { 0xff } else { 0 })
+}
+
+/// Left pad some bytes to 32 bytes.
+fn left_pad(bytes: &[u8], fill: u8) -> Result<[u8; 32], String> {
+    let mut padded = vec![fill; 32usize.saturating_sub(bytes.len())];
+    padded.extend_from_slice(bytes);
+    padded.try_into().map_err(|_| "value is longer than 32 bytes".to_string())
+}
+
+/// Get a string out of a JSON value.
+fn as_str(value: &Value) -> Result<&str, String> {
+    value.as_str().ok_or_else(|| "value must be a string".to_string())
+}
+
+/// Decode a hex string, with or without a `0x` prefix.
+fn decode_hex(value: &str) -> Result<Vec<u8>, String> {
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    hex::decode(digits).map_err(|e| format!("Invalid hex string {value}: {e}"))
+}
+
+/// Hash some bytes using keccak-256.
+fn keccak(bytes: &[u8]) -> [u8; 32] {
+    Keccak256::digest(bytes).into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_test::*;
+
+    // The example from the EIP-712 specification.
+    const MAIL: &str = r#"{
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" }
+            ],
+            "Person": [
+                { "name": "name", "type": "string" },
+                { "name": "wallet", "type": "address" }
+            ],
+            "Mail": [
+                { "name": "from", "type": "Person" },
+                { "name": "to", "type": "Person" },
+                { "name": "contents", "type": "string" }
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        },
+        "message": {
+            "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+            "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+            "contents": "Hello, Bob!"
+        }
+    }"#;
+
+    const MAIL_DIGEST: &str = "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2";
+
+    fn hex(bytes: [u8; 32]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    #[wasm_bindgen_test]
+    fn mail() -> Result<(), JsValue> {
+        assert_eq!(hex(hash_typed_data(MAIL)?), MAIL_DIGEST);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn inferred_domain_type() -> Result<(), JsValue> {
+        let mut typed_data: Value = serde_json::from_str(MAIL).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        if let Some(types) = typed_data.get_mut("types").and_then(Value::as_object_mut) {
+            types.remove(DOMAIN_TYPE);
+        }
+        assert_eq!(hex(hash_typed_data(&typed_data.to_string())?), MAIL_DIGEST);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn invalid_typed_data() {
+        assert!(hash_typed_data("{}").is_err());
+        assert!(hash_typed_data(&MAIL.replace("\"Cow\"", "5")).is_err());
+        assert!(hash_typed_data(&MAIL.replace("\"chainId\": 1", "\"chainId\": -1")).is_err());
+        assert!(hash_typed_data(&MAIL.replace("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826", "0x1234")).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn integers() {
+        assert_eq!(encode_integer(&Value::from(-1), 8, true).ok(), Some([0xff; 32]));
+        assert!(encode_integer(&Value::from(-129), 8, true).is_err());
+        assert!(encode_integer(&Value::from(256), 8, false).is_err());
+        let mut expected = [0; 32];
+        if let Some(last) = expected.last_mut() {
+            *last = 0xff;
+        }
+        assert_eq!(encode_integer(&Value::from("0xff"), 8, false).ok(), Some(expected));
+    }
+}
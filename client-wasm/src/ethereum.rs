@@ -0,0 +1,462 @@
+//! Ethereum specific helpers.
+use crate::{
+    ecdsa::{normalize_s, parse_public_key, public_key_from_value, recovery_id},
+    errors::{JsResult, ValueError},
+    key_formats::decode_hex,
+    rlp::Item,
+    values::{EcdsaSignature, NadaValue},
+};
+use nillion_client_core::generic_ec::{curves::Secp256k1, Point};
+use num_bigint::BigUint;
+use serde::Deserialize;
+use sha3::{Digest, Keccak256};
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+
+/// The type byte of EIP-1559 transactions.
+const EIP1559_TRANSACTION_TYPE: u8 = 2;
+
+/// The type of legacy transactions.
+const LEGACY_TRANSACTION_TYPE: u8 = 0;
+
+#[wasm_bindgen]
+impl NadaValue {
+    /// Derive the Ethereum address for an `EcdsaPublicKey` value.
+    ///
+    /// @return {string} The address, `0x` prefixed and checksummed as described in EIP-55
+    /// @throws {Error} if the value is not a valid ecdsa public key.
+    ///
+    /// @example
+    /// const address = publicKey.to_ethereum_address();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_ethereum_address(&self) -> JsResult<String> {
+        let public_key = parse_public_key(&public_key_from_value(self)?)?;
+        Ok(checksum_address(&address_from_public_key(&public_key)))
+    }
+}
+
+/// The last 20 bytes of the keccak-256 hash of the uncompressed public key, without its SEC1 prefix.
+pub(crate) fn address_from_public_key(public_key: &Point<Secp256k1>) -> [u8; 20] {
+    let encoded = public_key.to_bytes(false);
+    let hash = Keccak256::digest(encoded.get(1..).unwrap_or_default());
+    let mut address = [0; 20];
+    for (output, byte) in address.iter_mut().zip(hash.iter().skip(12)) {
+        *output = *byte;
+    }
+    address
+}
+
+/// Hex encode an address, uppercasing every letter whose nibble in the hash of the lowercase address is 8 or above.
+pub(crate) fn checksum_address(address: &[u8; 20]) -> String {
+    let lowercase: String = address.iter().map(|byte| format!("{byte:02x}")).collect();
+    let hash = Keccak256::digest(lowercase.as_bytes());
+    let nibbles = hash.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]);
+    let checksummed: String = lowercase
+        .chars()
+        .zip(nibbles)
+        .map(|(c, nibble)| if nibble >= 8 { c.to_ascii_uppercase() } else { c })
+        .collect();
+    format!("0x{checksummed}")
+}
+
+/// An unsigned Ethereum transaction, either a legacy EIP-155 transaction or an EIP-1559 transaction.
+#[wasm_bindgen]
+pub struct EthereumTransaction {
+    chain_id: u64,
+    nonce: Vec<u8>,
+    fees: Fees,
+    gas_limit: Vec<u8>,
+    to: Vec<u8>,
+    value: Vec<u8>,
+    data: Vec<u8>,
+}
+
+/// The fee fields, which depend on the transaction type.
+enum Fees {
+    Legacy { gas_price: Vec<u8> },
+    Eip1559 { max_priority_fee_per_gas: Vec<u8>, max_fee_per_gas: Vec<u8>, access_list: Vec<(Vec<u8>, Vec<Vec<u8>>)> },
+}
+
+#[wasm_bindgen]
+impl EthereumTransaction {
+    /// Create a new unsigned transaction out of its fields.
+    ///
+    /// Quantities can be numbers, decimal strings or `0x` prefixed hex strings. The transaction is an EIP-1559
+    /// transaction if `type` is 2 or `maxFeePerGas` is set, and a legacy EIP-155 transaction if `type` is 0 or
+    /// `gasPrice` is set.
+    ///
+    /// @param {object} fields - The transaction fields: `chainId`, `nonce`, `gasLimit`, `to`, `value`, `data` and
+    ///   either `gasPrice` or `maxPriorityFeePerGas`, `maxFeePerGas` and `accessList`
+    /// @return {EthereumTransaction} The unsigned transaction
+    /// @throws {Error} if a field is missing or malformed.
+    ///
+    /// @example
+    /// const tx = new EthereumTransaction({ chainId: 1, nonce: 0, maxPriorityFeePerGas: "1000000000",
+    ///   maxFeePerGas: "30000000000", gasLimit: 21000, to: "0x3535...", value: "1000000000000000000" });
+    #[wasm_bindgen(constructor, skip_jsdoc)]
+    pub fn new(fields: JsValue) -> JsResult<EthereumTransaction> {
+        let fields: TransactionFields = serde_wasm_bindgen::from_value(fields)
+            .map_err(|e| ValueError::new_err(&format!("Invalid transaction: {e}")))?;
+        Self::from_fields(fields).map_err(|e| ValueError::new_err(&format!("Invalid transaction: {e}")))
+    }
+
+    /// The keccak-256 hash to be signed for this transaction.
+    pub fn signing_hash(&self) -> Vec<u8> {
+        keccak(&self.encode(&[])).to_vec()
+    }
+
+    /// The hash to be signed for this transaction, as an `EcdsaDigestMessage` value.
+    ///
+    /// @return {NadaValue} The digest to sign
+    ///
+    /// @example
+    /// const digest = tx.to_digest_message();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_digest_message(&self) -> JsResult<NadaValue> {
+        NadaValue::new_ecdsa_digest_message(self.signing_hash())
+    }
+
+    /// Encode this transaction along with its signature, ready to be broadcast with `eth_sendRawTransaction`.
+    ///
+    /// The signature is normalized to have a low s component, as required by EIP-2.
+    ///
+    /// @param {EcdsaSignature} signature - The signature over this transaction's signing hash
+    /// @param {NadaValue} public_key - The `EcdsaPublicKey` value of the sender
+    /// @return {Uint8Array} The signed raw transaction
+    /// @throws {Error} if the signature is not valid for the public key and this transaction.
+    ///
+    /// @example
+    /// const raw = tx.to_signed_bytes(signature, publicKey);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_signed_bytes(&self, signature: &EcdsaSignature, public_key: &NadaValue) -> JsResult<Vec<u8>> {
+        let public_key = parse_public_key(&public_key_from_value(public_key)?)?;
+        let (r, s) = signature.scalars()?;
+        let s = normalize_s(s);
+        let recovery_id = recovery_id(&public_key, &keccak(&self.encode(&[])), &r, &s)?;
+        let v = match self.fees {
+            Fees::Legacy { .. } => self
+                .chain_id
+                .checked_mul(2)
+                .and_then(|v| v.checked_add(35))
+                .and_then(|v| v.checked_add(u64::from(recovery_id)))
+                .ok_or_else(|| ValueError::new_err("Chain id is too large"))?,
+            Fees::Eip1559 { .. } => u64::from(recovery_id),
+        };
+        let (v, r, s) = (v.to_be_bytes(), r.to_be_bytes(), s.to_be_bytes());
+        Ok(self.encode(&[&v, &r, &s]))
+    }
+}
+
+impl EthereumTransaction {
+    /// Validate and convert the transaction fields.
+    fn from_fields(fields: TransactionFields) -> Result<Self, String> {
+        let eip1559 = match &fields.transaction_type {
+            // Quantities are minimal big endian bytes, so a single byte holds the numeric type, zero included.
+            Some(transaction_type) => match transaction_type.to_bytes("type")?.as_slice() {
+                [LEGACY_TRANSACTION_TYPE] => false,
+                [EIP1559_TRANSACTION_TYPE] => true,
+                _ => return Err("only legacy and EIP-1559 transactions are supported".to_string()),
+            },
+            None => fields.max_fee_per_gas.is_some(),
+        };
+        let fees = if eip1559 {
+            let access_list = fields
+                .access_list
+                .iter()
+                .map(|entry| {
+                    let storage_keys: Result<Vec<_>, String> = entry
+                        .storage_keys
+                        .iter()
+                        .map(|key| check_length(hex_field(key, "storage key")?, "storage key", 32))
+                        .collect();
+                    let address = hex_field(&entry.address, "access list address")?;
+                    Ok((check_length(address, "access list address", 20)?, storage_keys?))
+                })
+                .collect::<Result<_, String>>()?;
+            Fees::Eip1559 {
+                max_priority_fee_per_gas: required(&fields.max_priority_fee_per_gas, "maxPriorityFeePerGas")?,
+                max_fee_per_gas: required(&fields.max_fee_per_gas, "maxFeePerGas")?,
+                access_list,
+            }
+        } else {
+            Fees::Legacy { gas_price: required(&fields.gas_price, "gasPrice")? }
+        };
+        let chain_id = fields.chain_id.to_bytes("chainId")?;
+        let chain_id = BigUint::from_bytes_be(&chain_id);
+        let chain_id = u64::try_from(chain_id).map_err(|_| "chainId is too large".to_string())?;
+        Ok(Self {
+            chain_id,
+            nonce: fields.nonce.to_bytes("nonce")?,
+            fees,
+            gas_limit: fields.gas_limit.to_bytes("gasLimit")?,
+            to: fields
+                .to
+                .as_deref()
+                .map(|to| check_length(hex_field(to, "to")?, "to", 20))
+                .transpose()?
+                .unwrap_or_default(),
+            value: fields.value.as_ref().map(|value| value.to_bytes("value")).transpose()?.unwrap_or_default(),
+            data: fields.data.as_deref().map(|data| hex_field(data, "data")).transpose()?.unwrap_or_default(),
+        })
+    }
+
+    /// Encode this transaction, either with the given `v`, `r` and `s` signature fields or, if they are empty, in the
+    /// form that is signed.
+    fn encode(&self, signature: &[&[u8]]) -> Vec<u8> {
+        let mut items = Vec::new();
+        if let Fees::Eip1559 { .. } = self.fees {
+            items.push(Item::integer(&self.chain_id.to_be_bytes()));
+        }
+        items.push(Item::integer(&self.nonce));
+        match &self.fees {
+            Fees::Legacy { gas_price } => items.push(Item::integer(gas_price)),
+            Fees::Eip1559 { max_priority_fee_per_gas, max_fee_per_gas, .. } => {
+                items.push(Item::integer(max_priority_fee_per_gas));
+                items.push(Item::integer(max_fee_per_gas));
+            }
+        }
+        items.push(Item::integer(&self.gas_limit));
+        items.push(Item::Bytes(self.to.clone()));
+        items.push(Item::integer(&self.value));
+        items.push(Item::Bytes(self.data.clone()));
+        if let Fees::Eip1559 { access_list, .. } = &self.fees {
+            let access_list = access_list
+                .iter()
+                .map(|(address, storage_keys)| {
+                    let storage_keys = storage_keys.iter().map(|key| Item::Bytes(key.clone())).collect();
+                    Item::List(vec![Item::Bytes(address.clone()), Item::List(storage_keys)])
+                })
+                .collect();
+            items.push(Item::List(access_list));
+        }
+        match (&self.fees, signature.is_empty()) {
+            // EIP-155 replay protection signs over the chain id followed by two empty fields.
+            (Fees::Legacy { .. }, true) => {
+                items.push(Item::integer(&self.chain_id.to_be_bytes()));
+                items.push(Item::integer(&[]));
+                items.push(Item::integer(&[]));
+            }
+            _ => items.extend(signature.iter().map(|field| Item::integer(field))),
+        }
+        let encoded = Item::List(items).encode();
+        match self.fees {
+            Fees::Legacy { .. } => encoded,
+            Fees::Eip1559 { .. } => {
+                let mut typed = vec![EIP1559_TRANSACTION_TYPE];
+                typed.extend(encoded);
+                typed
+            }
+        }
+    }
+}
+
+/// The transaction fields as provided from javascript.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionFields {
+    #[serde(rename = "type", default)]
+    transaction_type: Option<Quantity>,
+    chain_id: Quantity,
+    nonce: Quantity,
+    #[serde(default)]
+    gas_price: Option<Quantity>,
+    #[serde(default)]
+    max_priority_fee_per_gas: Option<Quantity>,
+    #[serde(default)]
+    max_fee_per_gas: Option<Quantity>,
+    #[serde(alias = "gas")]
+    gas_limit: Quantity,
+    #[serde(default)]
+    to: Option<String>,
+    #[serde(default)]
+    value: Option<Quantity>,
+    #[serde(default, alias = "input")]
+    data: Option<String>,
+    #[serde(default)]
+    access_list: Vec<AccessListEntry>,
+}
+
+/// An entry in an EIP-2930 access list.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccessListEntry {
+    address: String,
+    #[serde(default)]
+    storage_keys: Vec<String>,
+}
+
+/// An unsigned integer, either as a number or as a decimal or `0x` prefixed hex string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Quantity {
+    Number(u64),
+    Text(String),
+}
+
+impl Quantity {
+    /// The big endian bytes of this quantity.
+    fn to_bytes(&self, field: &str) -> Result<Vec<u8>, String> {
+        let value = match self {
+            Quantity::Number(value) => Some(BigUint::from(*value)),
+            Quantity::Text(text) => match text.strip_prefix("0x") {
+                Some(digits) => BigUint::parse_bytes(digits.as_bytes(), 16),
+                None => BigUint::parse_bytes(text.as_bytes(), 10),
+            },
+        };
+        value.map(|value| value.to_bytes_be()).ok_or_else(|| format!("{field} is not a valid quantity"))
+    }
+}
+
+/// The keccak-256 hash of an encoded transaction.
+fn keccak(encoded: &[u8]) -> [u8; 32] {
+    Keccak256::digest(encoded).into()
+}
+
+/// Get the bytes of a quantity that is required for the transaction type.
+fn required(quantity: &Option<Quantity>, field: &str) -> Result<Vec<u8>, String> {
+    quantity.as_ref().ok_or_else(|| format!("{field} is required"))?.to_bytes(field)
+}
+
+/// Decode a hex transaction field.
+fn hex_field(value: &str, field: &str) -> Result<Vec<u8>, String> {
+    Ok(decode_hex(value).map_err(|_| format!("{field} is not valid hex"))?.to_vec())
+}
+
+/// Check a decoded transaction field has the expected length.
+fn check_length(bytes: Vec<u8>, field: &str, length: usize) -> Result<Vec<u8>, String> {
+    if bytes.len() == length { Ok(bytes) } else { Err(format!("{field} must be {length} bytes long")) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ecdsa::recover_public_key, keys::arithmetic::neg};
+    use nillion_client_core::generic_ec::Scalar;
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_test::*;
+
+    fn transaction(fields: &str) -> Result<EthereumTransaction, JsValue> {
+        Ok(EthereumTransaction::new(js_sys::JSON::parse(fields)?)?)
+    }
+
+    #[wasm_bindgen_test]
+    fn checksum() -> Result<(), JsValue> {
+        // Test vectors from EIP-55.
+        let addresses = [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        ];
+        for address in addresses {
+            let bytes: Vec<u8> = (2..address.len())
+                .step_by(2)
+                .filter_map(|i| address.get(i..i.saturating_add(2)))
+                .filter_map(|hex| u8::from_str_radix(hex, 16).ok())
+                .collect();
+            let bytes: [u8; 20] = bytes.try_into().map_err(|_| JsValue::from_str("invalid address"))?;
+            assert_eq!(checksum_address(&bytes), address);
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn public_key_address() -> Result<(), JsValue> {
+        // The public key for the private key `1`, i.e. the generator point.
+        let mut public_key = vec![2];
+        public_key.extend_from_slice(&[
+            0x79, 0xbe, 0x66, 0x7e, 0xf9, 0xdc, 0xbb, 0xac, 0x55, 0xa0, 0x62, 0x95, 0xce, 0x87, 0x0b, 0x07, 0x02, 0x9b,
+            0xfc, 0xdb, 0x2d, 0xce, 0x28, 0xd9, 0x59, 0xf2, 0x81, 0x5b, 0x16, 0xf8, 0x17, 0x98,
+        ]);
+        let public_key = NadaValue::new_ecdsa_public_key(public_key)?;
+        assert_eq!(public_key.to_ethereum_address()?, "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf");
+
+        let digest = NadaValue::new_ecdsa_digest_message(vec![3; 32])?;
+        assert!(digest.to_ethereum_address().is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn legacy_transaction() -> Result<(), JsValue> {
+        // The example from EIP-155.
+        let tx = transaction(
+            r#"{"chainId": 1, "nonce": 9, "gasPrice": "20000000000", "gasLimit": 21000,
+                "to": "0x3535353535353535353535353535353535353535", "value": "1000000000000000000"}"#,
+        )?;
+        assert_eq!(hex::encode(tx.signing_hash()), "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53");
+        assert_eq!(tx.to_digest_message()?.to_byte_array()?.to_vec(), tx.signing_hash());
+
+        let decimal = |value: &str| BigUint::parse_bytes(value.as_bytes(), 10).unwrap_or_default().to_bytes_be();
+        let signature = EcdsaSignature::new(
+            decimal("18515461264373351373200002665853028612451056578545711640558177340181847433846"),
+            decimal("46948507304638947509940763649030358759909902576025900602547168820602576006531"),
+        );
+        let private_key = NadaValue::new_ecdsa_private_key_from_hex(
+            "4646464646464646464646464646464646464646464646464646464646464646",
+        )?;
+        let signed = tx.to_signed_bytes(&signature, &private_key.public_key()?)?;
+        assert_eq!(
+            hex::encode(signed),
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+        );
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn eip1559_transaction() -> Result<(), JsValue> {
+        let tx = transaction(
+            r#"{"type": "0x2", "chainId": "0x1", "nonce": "0x0", "maxPriorityFeePerGas": "1000000000",
+                "maxFeePerGas": "30000000000", "gas": 21000, "to": "0x3535353535353535353535353535353535353535",
+                "value": "0xde0b6b3a7640000", "data": "0x", "accessList": [{
+                    "address": "0x3535353535353535353535353535353535353535",
+                    "storageKeys": ["0x0000000000000000000000000000000000000000000000000000000000000001"]
+                }]}"#,
+        )?;
+        assert_eq!(hex::encode(tx.signing_hash()), "2e16243289b900a6d80e5717c5fa04f9718e8cfda4b5a26cb685ba57b84f5061");
+
+        // Any r and s are a valid signature for the public key recovered out of them.
+        let generator = Point::<Secp256k1>::generator().to_bytes(true);
+        let r = Scalar::<Secp256k1>::from_be_bytes_mod_order(generator.get(1..).unwrap_or_default());
+        let hash: [u8; 32] = tx.signing_hash().try_into().map_err(|_| "invalid hash")?;
+        let public_key = recover_public_key(&hash, &r, &Scalar::one(), 1).ok_or("invalid signature")?;
+        let public_key = NadaValue::new_ecdsa_public_key(public_key.to_bytes(true).to_vec())?;
+
+        // A high s component is normalized.
+        let signature =
+            EcdsaSignature::new(r.to_be_bytes().to_vec(), neg(Scalar::<Secp256k1>::one()).to_be_bytes().to_vec());
+        let signed = tx.to_signed_bytes(&signature, &public_key)?;
+        let mut suffix = vec![0x01, 0xa0];
+        suffix.extend_from_slice(&r.to_be_bytes());
+        suffix.push(0x01);
+        assert_eq!(signed.first(), Some(&EIP1559_TRANSACTION_TYPE));
+        assert!(signed.ends_with(&suffix));
+
+        let other = NadaValue::new_ecdsa_private_key_from_hex("01")?.public_key()?;
+        assert!(tx.to_signed_bytes(&signature, &other).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn explicit_legacy_type() -> Result<(), JsValue> {
+        let fields = r#""chainId": 1, "nonce": 9, "gasPrice": "20000000000", "gasLimit": 21000,
+            "to": "0x3535353535353535353535353535353535353535", "value": "1000000000000000000""#;
+        let untyped = transaction(&format!("{{{fields}}}"))?;
+        for transaction_type in ["0", "\"0x0\"", "\"0\""] {
+            let typed = transaction(&format!(r#"{{"type": {transaction_type}, {fields}}}"#))?;
+            assert_eq!(typed.signing_hash(), untyped.signing_hash());
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn invalid_transaction() {
+        let invalid = [
+            r#"{"chainId": 1, "nonce": 0, "gasLimit": 21000}"#,
+            r#"{"type": 1, "chainId": 1, "nonce": 0, "gasPrice": 1, "gasLimit": 21000}"#,
+            r#"{"chainId": 1, "nonce": 0, "gasPrice": 1, "gasLimit": 21000, "to": "0x35"}"#,
+            r#"{"chainId": 1, "nonce": 0, "gasPrice": 1, "gasLimit": 21000, "data": "0xzz"}"#,
+            r#"{"chainId": 1, "nonce": "-1", "gasPrice": 1, "gasLimit": 21000}"#,
+        ];
+        for fields in invalid {
+            assert!(transaction(fields).is_err(), "{fields}");
+        }
+    }
+}
@@ -0,0 +1,462 @@
+//! BIP-32 and SLIP-10 hierarchical deterministic key derivation.
+use crate::{
+    errors::{JsResult, ValueError},
+    key_formats::{ecdsa_private_key, eddsa_private_key},
+    keys::{
+        arithmetic::{add, add_points, mul_generator},
+        derive_public_key, scalar,
+    },
+    values::NadaValue,
+};
+use ed25519_dalek::SigningKey;
+use hmac::{Hmac, Mac};
+use nillion_client_core::generic_ec::{curves::Secp256k1, Point, Scalar, SecretScalar};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256, Sha512};
+use wasm_bindgen::prelude::wasm_bindgen;
+use zeroize::{Zeroize, Zeroizing};
+
+/// The HMAC key used to derive BIP-32 master keys.
+const BIP32_SEED_KEY: &[u8] = b"Bitcoin seed";
+
+/// The HMAC key used to derive SLIP-10 Ed25519 master keys.
+const SLIP10_ED25519_SEED_KEY: &[u8] = b"ed25519 seed";
+
+/// The offset of hardened child indexes.
+const HARDENED: u32 = 0x8000_0000;
+
+/// The length of a serialized extended key, without its checksum.
+const EXTENDED_KEY_LENGTH: usize = 78;
+
+/// The mainnet and testnet versions of serialized extended private keys.
+const XPRV_VERSIONS: [[u8; 4]; 2] = [[0x04, 0x88, 0xad, 0xe4], [0x04, 0x35, 0x83, 0x94]];
+
+/// The version of serialized mainnet extended public keys.
+const XPUB_VERSION: [u8; 4] = [0x04, 0x88, 0xb2, 0x1e];
+
+/// The mainnet and testnet versions of serialized extended public keys.
+const XPUB_VERSIONS: [[u8; 4]; 2] = [XPUB_VERSION, [0x04, 0x35, 0x87, 0xcf]];
+
+#[wasm_bindgen]
+impl NadaValue {
+    /// Derive an ecdsa private key from a seed using BIP-32.
+    ///
+    /// @param {Uint8Array} seed - The seed, e.g. one obtained from a BIP-39 mnemonic
+    /// @param {string} path - The derivation path, e.g. `m/44'/60'/0'/0/0`
+    /// @return {NadaValue} The `EcdsaPrivateKey` value
+    /// @throws {Error} if the path is malformed.
+    ///
+    /// @example
+    /// const value = NadaValue.derive_ecdsa_private_key_from_seed(seed, "m/44'/60'/0'/0/0");
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn derive_ecdsa_private_key_from_seed(seed: Vec<u8>, path: &str) -> JsResult<NadaValue> {
+        let seed = Zeroizing::new(seed);
+        let key = ExtendedPrivateKey::from_seed(&seed)
+            .and_then(|key| key.derive_path(&parse_path(path)?))
+            .map_err(|e| ValueError::new_err(&e))?;
+        ecdsa_private_key(&key.private_key_bytes())
+    }
+
+    /// Derive an ecdsa private key from a BIP-32 extended private key.
+    ///
+    /// @param {string} xprv - The base58 encoded extended private key
+    /// @param {string} path - The derivation path, relative to the extended key, e.g. `m/0/0`
+    /// @return {NadaValue} The `EcdsaPrivateKey` value
+    /// @throws {Error} if the extended key or the path are malformed.
+    ///
+    /// @example
+    /// const value = NadaValue.derive_ecdsa_private_key_from_xprv("xprv9s21ZrQH143K...", "m/0/0");
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn derive_ecdsa_private_key_from_xprv(xprv: &str, path: &str) -> JsResult<NadaValue> {
+        let key = ExtendedPrivateKey::decode(xprv)
+            .and_then(|key| key.derive_path(&parse_path(path)?))
+            .map_err(|e| ValueError::new_err(&e))?;
+        ecdsa_private_key(&key.private_key_bytes())
+    }
+
+    /// Derive the BIP-32 extended public key for a path from a seed.
+    ///
+    /// The extended public key can be handed to watch-only services so they can derive public keys on their own.
+    ///
+    /// @param {Uint8Array} seed - The seed, e.g. one obtained from a BIP-39 mnemonic
+    /// @param {string} path - The derivation path, e.g. `m/44'/60'/0'`
+    /// @return {string} The base58 encoded extended public key
+    /// @throws {Error} if the path is malformed.
+    ///
+    /// @example
+    /// const xpub = NadaValue.derive_ecdsa_xpub_from_seed(seed, "m/44'/60'/0'");
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn derive_ecdsa_xpub_from_seed(seed: Vec<u8>, path: &str) -> JsResult<String> {
+        let seed = Zeroizing::new(seed);
+        let key = ExtendedPrivateKey::from_seed(&seed)
+            .and_then(|key| key.derive_path(&parse_path(path)?))
+            .map_err(|e| ValueError::new_err(&e))?;
+        Ok(key.public_key().encode())
+    }
+
+    /// Derive an ecdsa public key from a BIP-32 extended public key.
+    ///
+    /// Only non-hardened derivation is possible from an extended public key.
+    ///
+    /// @param {string} xpub - The base58 encoded extended public key
+    /// @param {string} path - The derivation path, relative to the extended key, e.g. `m/0/0`
+    /// @return {NadaValue} The `EcdsaPublicKey` value
+    /// @throws {Error} if the extended key or the path are malformed, or the path contains hardened indexes.
+    ///
+    /// @example
+    /// const value = NadaValue.derive_ecdsa_public_key_from_xpub("xpub661MyMwAqRbc...", "m/0/0");
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn derive_ecdsa_public_key_from_xpub(xpub: &str, path: &str) -> JsResult<NadaValue> {
+        let key = ExtendedPublicKey::decode(xpub)
+            .and_then(|key| key.derive_path(&parse_path(path)?))
+            .map_err(|e| ValueError::new_err(&e))?;
+        NadaValue::new_ecdsa_public_key(key.key.to_bytes(true).to_vec())
+    }
+
+    /// Derive an eddsa private key from a seed using SLIP-10.
+    ///
+    /// SLIP-10 only supports hardened derivation for Ed25519, so every index in the path must be hardened.
+    ///
+    /// @param {Uint8Array} seed - The seed, e.g. one obtained from a BIP-39 mnemonic
+    /// @param {string} path - The derivation path, e.g. `m/44'/501'/0'/0'`
+    /// @return {NadaValue} The `EddsaPrivateKey` value
+    /// @throws {Error} if the path is malformed or contains non-hardened indexes.
+    ///
+    /// @example
+    /// const value = NadaValue.derive_eddsa_private_key_from_seed(seed, "m/44'/501'/0'/0'");
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn derive_eddsa_private_key_from_seed(seed: Vec<u8>, path: &str) -> JsResult<NadaValue> {
+        let seed = Zeroizing::new(seed);
+        let key =
+            parse_path(path).and_then(|path| derive_ed25519_key(&seed, &path)).map_err(|e| ValueError::new_err(&e))?;
+        eddsa_private_key(&SigningKey::from_bytes(&key))
+    }
+}
+
+/// A BIP-32 extended private key.
+struct ExtendedPrivateKey {
+    key: SecretScalar<Secp256k1>,
+    chain_code: Zeroizing<[u8; 32]>,
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+}
+
+impl ExtendedPrivateKey {
+    /// Derive the master key for a seed.
+    fn from_seed(seed: &[u8]) -> Result<Self, String> {
+        let output = hmac_sha512(BIP32_SEED_KEY, &[seed])?;
+        let (key, chain_code) = split_output(&output)?;
+        let key = SecretScalar::from_be_bytes(key).map_err(|_| "Seed produces an invalid master key".to_string())?;
+        if scalar(&key).is_zero() {
+            return Err("Seed produces an invalid master key".to_string());
+        }
+        Ok(Self { key, chain_code, depth: 0, parent_fingerprint: [0; 4], child_number: 0 })
+    }
+
+    /// Decode a base58 encoded extended private key.
+    fn decode(encoded: &str) -> Result<Self, String> {
+        let bytes = decode_extended_key(encoded)?;
+        let header = ExtendedKeyHeader::parse(&bytes, &XPRV_VERSIONS)?;
+        let key = match bytes.get(45..) {
+            Some([0, key @ ..]) => {
+                SecretScalar::from_be_bytes(key).map_err(|_| "Invalid extended private key".to_string())?
+            }
+            _ => return Err("Invalid extended private key".to_string()),
+        };
+        if scalar(&key).is_zero() {
+            return Err("Invalid extended private key".to_string());
+        }
+        Ok(Self {
+            key,
+            chain_code: header.chain_code,
+            depth: header.depth,
+            parent_fingerprint: header.parent_fingerprint,
+            child_number: header.child_number,
+        })
+    }
+
+    /// Derive the key at a path relative to this key.
+    fn derive_path(self, path: &[u32]) -> Result<Self, String> {
+        path.iter().try_fold(self, |key, index| key.derive_child(*index))
+    }
+
+    /// Derive the child key at an index.
+    fn derive_child(&self, index: u32) -> Result<Self, String> {
+        let public_key = derive_public_key(&self.key);
+        let output = if index >= HARDENED {
+            hmac_sha512(self.chain_code.as_slice(), &[&[0], &self.private_key_bytes(), &index.to_be_bytes()])?
+        } else {
+            hmac_sha512(self.chain_code.as_slice(), &[&public_key.to_bytes(true), &index.to_be_bytes()])?
+        };
+        let (tweak, chain_code) = split_output(&output)?;
+        let tweak = Scalar::<Secp256k1>::from_be_bytes(tweak).map_err(|_| invalid_child(index))?;
+        let mut key = add(tweak, *scalar(&self.key));
+        if key.is_zero() {
+            return Err(invalid_child(index));
+        }
+        Ok(Self {
+            key: SecretScalar::new(&mut key),
+            chain_code,
+            depth: self.depth.checked_add(1).ok_or_else(|| "Derivation path is too deep".to_string())?,
+            parent_fingerprint: fingerprint(&public_key),
+            child_number: index,
+        })
+    }
+
+    /// The extended public key for this key.
+    fn public_key(&self) -> ExtendedPublicKey {
+        ExtendedPublicKey {
+            key: derive_public_key(&self.key),
+            chain_code: *self.chain_code,
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+            child_number: self.child_number,
+        }
+    }
+
+    /// The big endian bytes of the private key.
+    fn private_key_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(scalar(&self.key).to_be_bytes().to_vec())
+    }
+}
+
+/// A BIP-32 extended public key.
+struct ExtendedPublicKey {
+    key: Point<Secp256k1>,
+    chain_code: [u8; 32],
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+}
+
+impl ExtendedPublicKey {
+    /// Decode a base58 encoded extended public key.
+    fn decode(encoded: &str) -> Result<Self, String> {
+        let bytes = decode_extended_key(encoded)?;
+        let header = ExtendedKeyHeader::parse(&bytes, &XPUB_VERSIONS)?;
+        let key = bytes
+            .get(45..)
+            .and_then(|key| Point::from_bytes(key).ok())
+            .filter(|key| !key.is_zero())
+            .ok_or_else(|| "Invalid extended public key".to_string())?;
+        Ok(Self {
+            key,
+            chain_code: *header.chain_code,
+            depth: header.depth,
+            parent_fingerprint: header.parent_fingerprint,
+            child_number: header.child_number,
+        })
+    }
+
+    /// Encode this key as a base58 mainnet extended public key.
+    fn encode(&self) -> String {
+        let mut bytes = Vec::with_capacity(EXTENDED_KEY_LENGTH);
+        bytes.extend_from_slice(&XPUB_VERSION);
+        bytes.push(self.depth);
+        bytes.extend_from_slice(&self.parent_fingerprint);
+        bytes.extend_from_slice(&self.child_number.to_be_bytes());
+        bytes.extend_from_slice(&self.chain_code);
+        bytes.extend_from_slice(&self.key.to_bytes(true));
+        bs58::encode(bytes).with_check().into_string()
+    }
+
+    /// Derive the key at a path relative to this key.
+    fn derive_path(self, path: &[u32]) -> Result<Self, String> {
+        path.iter().try_fold(self, |key, index| key.derive_child(*index))
+    }
+
+    /// Derive the child key at a non-hardened index.
+    fn derive_child(&self, index: u32) -> Result<Self, String> {
+        if index >= HARDENED {
+            return Err("Hardened keys can't be derived from an extended public key".to_string());
+        }
+        let output = hmac_sha512(&self.chain_code, &[&self.key.to_bytes(true), &index.to_be_bytes()])?;
+        let (tweak, chain_code) = split_output(&output)?;
+        let tweak = Scalar::<Secp256k1>::from_be_bytes(tweak).map_err(|_| invalid_child(index))?;
+        let key = add_points(mul_generator(tweak), self.key);
+        if key.is_zero() {
+            return Err(invalid_child(index));
+        }
+        Ok(Self {
+            key,
+            chain_code: *chain_code,
+            depth: self.depth.checked_add(1).ok_or_else(|| "Derivation path is too deep".to_string())?,
+            parent_fingerprint: fingerprint(&self.key),
+            child_number: index,
+        })
+    }
+}
+
+/// The fields shared by extended private and public keys.
+struct ExtendedKeyHeader {
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    chain_code: Zeroizing<[u8; 32]>,
+}
+
+impl ExtendedKeyHeader {
+    /// Parse the header of a serialized extended key, checking its version is one of the expected ones.
+    fn parse(bytes: &[u8], versions: &[[u8; 4]]) -> Result<Self, String> {
+        let field = |range: std::ops::Range<usize>| bytes.get(range).ok_or_else(|| "Invalid extended key".to_string());
+        if !versions.iter().any(|version| field(0..4).ok() == Some(version.as_slice())) {
+            return Err("Unsupported extended key version".to_string());
+        }
+        let depth = *bytes.get(4).ok_or_else(|| "Invalid extended key".to_string())?;
+        let parent_fingerprint: [u8; 4] = field(5..9)?.try_into().map_err(|_| "Invalid extended key".to_string())?;
+        let child_number: [u8; 4] = field(9..13)?.try_into().map_err(|_| "Invalid extended key".to_string())?;
+        let child_number = u32::from_be_bytes(child_number);
+        if depth == 0 && (parent_fingerprint != [0; 4] || child_number != 0) {
+            return Err("Invalid master extended key".to_string());
+        }
+        let mut chain_code = Zeroizing::new([0; 32]);
+        chain_code.copy_from_slice(field(13..45)?);
+        Ok(Self { depth, parent_fingerprint, child_number, chain_code })
+    }
+}
+
+/// Derive an Ed25519 private key from a seed using SLIP-10.
+fn derive_ed25519_key(seed: &[u8], path: &[u32]) -> Result<Zeroizing<[u8; 32]>, String> {
+    let output = hmac_sha512(SLIP10_ED25519_SEED_KEY, &[seed])?;
+    let (key, chain_code) = split_output(&output)?;
+    let mut key = Zeroizing::new(<[u8; 32]>::try_from(key).map_err(|_| "Invalid master key".to_string())?);
+    let mut chain_code = chain_code;
+    for index in path {
+        if *index < HARDENED {
+            return Err("Ed25519 keys can only be derived at hardened indexes".to_string());
+        }
+        let output = hmac_sha512(chain_code.as_slice(), &[&[0], key.as_slice(), &index.to_be_bytes()])?;
+        let (child_key, child_chain_code) = split_output(&output)?;
+        key.copy_from_slice(child_key);
+        chain_code = child_chain_code;
+    }
+    Ok(key)
+}
+
+/// Parse a derivation path such as `m/44'/60'/0'/0/0`, where hardened indexes are marked with `'` or `h`.
+fn parse_path(path: &str) -> Result<Vec<u32>, String> {
+    let mut components = path.trim().split('/');
+    if components.next() != Some("m") {
+        return Err(format!("Derivation path {path} must start with 'm'"));
+    }
+    components
+        .map(|component| {
+            let (index, hardened) = match component.strip_suffix(['\'', 'h', 'H']) {
+                Some(index) => (index, true),
+                None => (component, false),
+            };
+            let index: u32 = index.parse().map_err(|_| format!("Invalid derivation path index {component}"))?;
+            if index >= HARDENED {
+                return Err(format!("Derivation path index {component} is too large"));
+            }
+            Ok(if hardened { index | HARDENED } else { index })
+        })
+        .collect()
+}
+
+/// Decode a base58check extended key.
+fn decode_extended_key(encoded: &str) -> Result<Zeroizing<Vec<u8>>, String> {
+    let bytes = bs58::decode(encoded.trim())
+        .with_check(None)
+        .into_vec()
+        .map_err(|e| format!("Invalid extended key encoding: {e}"))?;
+    let bytes = Zeroizing::new(bytes);
+    if bytes.len() != EXTENDED_KEY_LENGTH {
+        return Err("Invalid extended key length".to_string());
+    }
+    Ok(bytes)
+}
+
+/// Compute HMAC-SHA512 over the concatenation of some data.
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> Result<Zeroizing<[u8; 64]>, String> {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).map_err(|e| format!("Invalid HMAC key: {e}"))?;
+    for part in data {
+        mac.update(part);
+    }
+    let mut digest = mac.finalize().into_bytes();
+    let mut output = Zeroizing::new([0; 64]);
+    output.copy_from_slice(&digest);
+    (*digest).zeroize();
+    Ok(output)
+}
+
+/// Split an HMAC-SHA512 output into its left half, the key material, and its right half, the chain code.
+fn split_output(output: &[u8; 64]) -> Result<(&[u8], Zeroizing<[u8; 32]>), String> {
+    let (key, chain_code) = output.split_at(32);
+    let chain_code = <[u8; 32]>::try_from(chain_code).map_err(|_| "Invalid chain code".to_string())?;
+    Ok((key, Zeroizing::new(chain_code)))
+}
+
+/// The first 4 bytes of the HASH160 of a compressed public key.
+fn fingerprint(public_key: &Point<Secp256k1>) -> [u8; 4] {
+    let hash = Ripemd160::digest(Sha256::digest(public_key.to_bytes(true)));
+    let mut fingerprint = [0; 4];
+    for (output, byte) in fingerprint.iter_mut().zip(hash.iter()) {
+        *output = *byte;
+    }
+    fingerprint
+}
+
+/// The error for the astronomically unlikely case of a child key being invalid.
+fn invalid_child(index: u32) -> String {
+    format!("Child key at index {index} is invalid, use the next index instead")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_test::*;
+
+    // Test vector 1 from BIP-32 and SLIP-10.
+    const SEED: &str = "000102030405060708090a0b0c0d0e0f";
+
+    fn seed() -> Vec<u8> {
+        hex::decode(SEED).unwrap_or_default()
+    }
+
+    #[wasm_bindgen_test]
+    fn bip32() -> Result<(), JsValue> {
+        let value = NadaValue::derive_ecdsa_private_key_from_seed(seed(), "m/0'/1/2'/2/1000000000")?;
+        assert_eq!(String::from(value.to_hex()?), "471b76e389e528d6de6d816857e012c5455051cad6660850e58372a6c3e6e7c8");
+
+        let xprv = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
+        let from_xprv = NadaValue::derive_ecdsa_private_key_from_xprv(xprv, "m/0'/1/2'/2/1000000000")?;
+        assert_eq!(from_xprv, value);
+
+        let xpub = NadaValue::derive_ecdsa_xpub_from_seed(seed(), "m/0'/1/2'")?;
+        assert_eq!(
+            xpub,
+            "xpub6D4BDPcP2GT577Vvch3R8wDkScZWzQzMMUm3PWbmWvVJrZwQY4VUNgqFJPMM3No2dFDFGTsxxpG5uJh7n7epu4trkrX7x7DogT5Uv6fcLW5"
+        );
+        let public_key = NadaValue::derive_ecdsa_public_key_from_xpub(&xpub, "m/2/1000000000")?;
+        assert_eq!(public_key, value.public_key()?);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn slip10_ed25519() -> Result<(), JsValue> {
+        let value = NadaValue::derive_eddsa_private_key_from_seed(seed(), "m/0'/1'/2'/2'/1000000000'")?;
+        let public_key = value.public_key()?.to_byte_array()?.to_vec();
+        assert_eq!(hex::encode(public_key), "3c24da049451555d51a7014a37337aa4e12d41e485abccfa46b47dfb2af54b7a");
+        assert!(NadaValue::derive_eddsa_private_key_from_seed(seed(), "m/0'/1").is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn invalid_paths() -> Result<(), JsValue> {
+        for path in ["", "0/1", "m/", "m/a", "m/2147483648", "m/1''"] {
+            assert!(NadaValue::derive_ecdsa_private_key_from_seed(seed(), path).is_err(), "{path}");
+        }
+        assert_eq!(
+            parse_path("m/44'/60h/0H/1").map_err(|e| JsValue::from_str(&e))?,
+            [44 | HARDENED, 60 | HARDENED, HARDENED, 1]
+        );
+
+        let xpub = NadaValue::derive_ecdsa_xpub_from_seed(seed(), "m")?;
+        assert!(NadaValue::derive_ecdsa_public_key_from_xpub(&xpub, "m/0'").is_err());
+        assert!(NadaValue::derive_ecdsa_private_key_from_xprv(&xpub, "m/0").is_err());
+        Ok(())
+    }
+}
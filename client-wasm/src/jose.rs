@@ -0,0 +1,286 @@
+//! JSON Web Signature utilities, to mint and verify compact JWS and JWT with threshold signatures.
+//!
+//! Only the `ES256K` (RFC 8812) and `EdDSA` with ed25519 (RFC 8037) algorithms are supported, since those are the
+//! signatures the network can produce.
+use crate::{
+    ecdsa, eddsa,
+    errors::{JsResult, ValueError},
+    key_formats::decode_base64,
+    values::{EcdsaSignature, EddsaSignature, NadaValue},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use nillion_client_core::generic_ec::{curves::Secp256k1, Scalar};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// A signing algorithm.
+#[derive(Clone, Copy, PartialEq)]
+enum Algorithm {
+    /// Ecdsa over secp256k1 with sha256.
+    Es256k,
+    /// Eddsa over ed25519.
+    EdDsa,
+}
+
+impl Algorithm {
+    /// The algorithm named in a JOSE header.
+    fn from_header(header: &[u8]) -> Result<Self, String> {
+        #[derive(Deserialize)]
+        struct Header {
+            alg: String,
+        }
+        let header: Header = serde_json::from_slice(header).map_err(|e| format!("invalid header: {e}"))?;
+        match header.alg.as_str() {
+            "ES256K" => Ok(Algorithm::Es256k),
+            "EdDSA" => Ok(Algorithm::EdDsa),
+            alg => Err(format!("unsupported algorithm {alg}")),
+        }
+    }
+
+    /// The name of the algorithm.
+    fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Es256k => "ES256K",
+            Algorithm::EdDsa => "EdDSA",
+        }
+    }
+}
+
+/// An unsigned JSON Web Signature, made of a protected header and a payload.
+#[wasm_bindgen]
+pub struct Jws {
+    algorithm: Algorithm,
+    signing_input: String,
+}
+
+#[wasm_bindgen]
+impl Jws {
+    /// Create a new JWS out of its header and claims.
+    ///
+    /// Both are encoded exactly as provided, so the signed token contains the same JSON.
+    ///
+    /// @param {string} header - The protected header as a JSON object, with an `alg` of either `ES256K` or `EdDSA`
+    /// @param {string} claims - The claims as a JSON object
+    /// @return {Jws} The unsigned JWS
+    /// @throws {Error} if the header or the claims are not JSON objects, or the algorithm is not supported.
+    ///
+    /// @example
+    /// const jws = new Jws(JSON.stringify({ alg: "ES256K", typ: "JWT" }), JSON.stringify({ sub: "did:nil:..." }));
+    #[wasm_bindgen(constructor, skip_jsdoc)]
+    pub fn new(header: &str, claims: &str) -> JsResult<Jws> {
+        let algorithm =
+            Algorithm::from_header(header.as_bytes()).map_err(|e| ValueError::new_err(&format!("Invalid JWS: {e}")))?;
+        serde_json::from_str::<Map<String, Value>>(claims)
+            .map_err(|e| ValueError::new_err(&format!("Invalid JWS: invalid claims: {e}")))?;
+        let signing_input = format!("{}.{}", URL_SAFE_NO_PAD.encode(header), URL_SAFE_NO_PAD.encode(claims));
+        Ok(Self { algorithm, signing_input })
+    }
+
+    /// The JWS signing input, `base64url(header) || '.' || base64url(claims)`.
+    pub fn signing_input(&self) -> String {
+        self.signing_input.clone()
+    }
+
+    /// The message to sign, as an `EcdsaDigestMessage` value for `ES256K` or an `EddsaMessage` value for `EdDSA`.
+    ///
+    /// @return {NadaValue} The message to sign
+    ///
+    /// @example
+    /// const message = jws.to_message();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_message(&self) -> JsResult<NadaValue> {
+        match self.algorithm {
+            Algorithm::Es256k => Ok(NadaValue::new_sha256_digest_message(self.signing_input.as_bytes())),
+            Algorithm::EdDsa => NadaValue::new_eddsa_message(self.signing_input.as_bytes().to_vec()),
+        }
+    }
+
+    /// Serialize this JWS in compact form using an ecdsa signature over its message.
+    ///
+    /// The signature is normalized to have a low s component.
+    ///
+    /// @param {EcdsaSignature} signature - The signature over the output of `to_message`
+    /// @return {string} The compact JWS
+    /// @throws {Error} if the JWS algorithm is not `ES256K` or the signature is malformed.
+    ///
+    /// @example
+    /// const token = jws.to_compact_ecdsa(signature);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_compact_ecdsa(&self, signature: &EcdsaSignature) -> JsResult<String> {
+        self.expect_algorithm(Algorithm::Es256k)?;
+        Ok(self.to_compact(&signature.to_compact_bytes()?))
+    }
+
+    /// Serialize this JWS in compact form using an eddsa signature over its message.
+    ///
+    /// @param {EddsaSignature} signature - The signature over the output of `to_message`
+    /// @return {string} The compact JWS
+    /// @throws {Error} if the JWS algorithm is not `EdDSA` or the signature is malformed.
+    ///
+    /// @example
+    /// const token = jws.to_compact_eddsa(signature);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_compact_eddsa(&self, signature: &EddsaSignature) -> JsResult<String> {
+        self.expect_algorithm(Algorithm::EdDsa)?;
+        Ok(self.to_compact(&signature.to_bytes()?))
+    }
+
+    /// Verify a compact JWS against a public key.
+    ///
+    /// @param {string} token - The compact JWS
+    /// @param {NadaValue} public_key - The `EcdsaPublicKey` value for `ES256K` tokens or the `EddsaPublicKey` value for
+    ///   `EdDSA` ones
+    /// @return {boolean} Whether the signature is valid
+    /// @throws {Error} if the token is malformed, or its algorithm doesn't match the public key.
+    ///
+    /// @example
+    /// const valid = Jws.verify(token, publicKey);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn verify(token: &str, public_key: &NadaValue) -> JsResult<bool> {
+        let mut parts = token.split('.');
+        let (Some(header), Some(payload), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(ValueError::new_err("Invalid JWS: compact JWS must have three parts"));
+        };
+        let algorithm = Algorithm::from_header(&decode_base64(header)?)
+            .map_err(|e| ValueError::new_err(&format!("Invalid JWS: {e}")))?;
+        let signature = decode_base64(signature)?;
+        let signing_input = format!("{header}.{payload}");
+        match (algorithm, &public_key.0) {
+            (Algorithm::Es256k, nillion_client_core::values::NadaValue::EcdsaPublicKey(_)) => {
+                let public_key = ecdsa::parse_public_key(&ecdsa::public_key_from_value(public_key)?)?;
+                let digest: [u8; 32] = Sha256::digest(signing_input.as_bytes()).into();
+                if signature.len() != 64 {
+                    return Ok(false);
+                }
+                let (r, s) = signature.split_at(32);
+                let (Ok(r), Ok(s)) = (Scalar::<Secp256k1>::from_be_bytes(r), Scalar::<Secp256k1>::from_be_bytes(s))
+                else {
+                    return Ok(false);
+                };
+                Ok(ecdsa::verify_prehashed(&public_key, &digest, &r, &s))
+            }
+            (Algorithm::EdDsa, nillion_client_core::values::NadaValue::EddsaPublicKey(_)) => {
+                let public_key = eddsa::public_key_from_value(public_key)?;
+                let Ok(signature) = <[u8; 64]>::try_from(signature) else {
+                    return Ok(false);
+                };
+                Ok(eddsa::verify_strict(&public_key, signing_input.as_bytes(), &signature))
+            }
+            (algorithm, _) => Err(ValueError::new_err(&format!(
+                "JWS algorithm {} doesn't match the public key provided",
+                algorithm.name()
+            ))),
+        }
+    }
+}
+
+impl Jws {
+    /// Ensure this JWS uses the given algorithm.
+    fn expect_algorithm(&self, algorithm: Algorithm) -> JsResult<()> {
+        if self.algorithm != algorithm {
+            return Err(ValueError::new_err(&format!(
+                "JWS algorithm is {}, not {}",
+                self.algorithm.name(),
+                algorithm.name()
+            )));
+        }
+        Ok(())
+    }
+
+    /// The compact serialization with the given signature.
+    fn to_compact(&self, signature: &[u8]) -> String {
+        format!("{}.{}", self.signing_input, URL_SAFE_NO_PAD.encode(signature))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use k256::ecdsa::signature::hazmat::PrehashSigner;
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_test::*;
+
+    fn decode(value: &str) -> Vec<u8> {
+        hex::decode(value).unwrap_or_default()
+    }
+
+    #[wasm_bindgen_test]
+    fn verify_rfc8037() -> Result<(), JsValue> {
+        // RFC 8037 appendix A.4.
+        let public_key = NadaValue::new_eddsa_public_key(decode(
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        ))?;
+        let token = "eyJhbGciOiJFZERTQSJ9.RXhhbXBsZSBvZiBFZDI1NTE5IHNpZ25pbmc.hgyY0il_MGCjP0JzlnLWG1PPOt7-09PGcvMg3AIbQR\
+                     6dWbhijcNR4ki4iylGjg5BhVsPt9g7sVvpAr_MuM0KAg";
+        assert!(Jws::verify(token, &public_key)?);
+        let tampered = token.replacen("RXhh", "RXhi", 1);
+        assert!(!Jws::verify(&tampered, &public_key)?);
+
+        let ecdsa_public_key = NadaValue::new_ecdsa_public_key(vec![2; 33])?;
+        assert!(Jws::verify(token, &ecdsa_public_key).is_err());
+        assert!(Jws::verify("eyJhbGciOiJFZERTQSJ9.e30", &public_key).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn eddsa_token() -> Result<(), JsValue> {
+        let signing_key = SigningKey::from_bytes(&[3; 32]);
+        let jws = Jws::new(r#"{"alg":"EdDSA","typ":"JWT"}"#, r#"{"sub":"nillion","iat":1700000000}"#)?;
+        assert_eq!(
+            jws.signing_input(),
+            "eyJhbGciOiJFZERTQSIsInR5cCI6IkpXVCJ9.eyJzdWIiOiJuaWxsaW9uIiwiaWF0IjoxNzAwMDAwMDAwfQ"
+        );
+        assert_eq!(jws.to_message()?.to_byte_array()?.to_vec(), jws.signing_input().into_bytes());
+
+        let signature = signing_key.sign(jws.signing_input().as_bytes()).to_bytes();
+        let (r, z) = signature.split_at(32);
+        let signature = EddsaSignature::new(r.to_vec(), z.to_vec());
+        let token = jws.to_compact_eddsa(&signature)?;
+        assert_eq!(
+            token,
+            format!(
+                "{}.{}",
+                jws.signing_input(),
+                URL_SAFE_NO_PAD.encode(r.iter().chain(z).copied().collect::<Vec<_>>())
+            )
+        );
+        let public_key = NadaValue::new_eddsa_public_key(signing_key.verifying_key().to_bytes().to_vec())?;
+        assert!(Jws::verify(&token, &public_key)?);
+        assert!(jws.to_compact_ecdsa(&EcdsaSignature::new(vec![1; 32], vec![1; 32])).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn ecdsa_token() -> Result<(), JsValue> {
+        let signing_key = k256::ecdsa::SigningKey::from_slice(&[5; 32]).map_err(|e| e.to_string())?;
+        let jws = Jws::new(r#"{"alg":"ES256K"}"#, r#"{"iss":"did:nil:test"}"#)?;
+        let digest = Sha256::digest(jws.signing_input().as_bytes());
+        assert_eq!(jws.to_message()?.to_byte_array()?.to_vec(), digest.to_vec());
+
+        let signature: k256::ecdsa::Signature = signing_key.sign_prehash(&digest).map_err(|e| e.to_string())?;
+        let (r, s) = signature.split_bytes();
+        let token = jws.to_compact_ecdsa(&EcdsaSignature::new(r.to_vec(), s.to_vec()))?;
+        let public_key = NadaValue::new_ecdsa_public_key(signing_key.verifying_key().to_sec1_bytes().to_vec())?;
+        assert!(Jws::verify(&token, &public_key)?);
+        let Some((_, encoded_signature)) = token.rsplit_once('.') else {
+            return Err("missing signature".into());
+        };
+        let other = Jws::new(r#"{"alg":"ES256K"}"#, r#"{"iss":"did:nil:other"}"#)?;
+        assert!(!Jws::verify(&format!("{}.{encoded_signature}", other.signing_input()), &public_key)?);
+        assert!(jws.to_compact_eddsa(&EddsaSignature::new(vec![1; 32], vec![1; 32])).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn invalid_jws() {
+        assert!(Jws::new(r#"{"alg":"HS256"}"#, "{}").is_err());
+        assert!(Jws::new(r#"{"typ":"JWT"}"#, "{}").is_err());
+        assert!(Jws::new(r#"{"alg":"EdDSA"}"#, "[1]").is_err());
+        assert!(Jws::new("{", "{}").is_err());
+    }
+}
@@ -0,0 +1,481 @@
+//! Threshold private key shares.
+//!
+//! Private keys can be split client side into shares of a random polynomial whose constant term is the key, so any
+//! `threshold` of them determine it. The VSS setup of those shares records the threshold and the point each share
+//! was evaluated at, which lets anyone check the public shares against the shared public key.
+use crate::{
+    errors::{JsResult, ValueError},
+    keys::{
+        arithmetic::{add, add_points, mul, mul_point, sub},
+        derive_public_key, random_scalar, scalar,
+    },
+    values::{EncryptedNadaValues, NadaValue},
+};
+use nillion_client_core::{
+    generic_ec::{
+        curves::{Ed25519, Secp256k1},
+        Curve, NonZero, Point, Scalar, SecretScalar,
+    },
+    key_share::VssSetup,
+    privatekey::{ThresholdPrivateKey, ThresholdPrivateKeyShare},
+};
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+use zeroize::Zeroizing;
+
+#[wasm_bindgen]
+impl NadaValue {
+    /// Split a private key into verifiable threshold key shares.
+    ///
+    /// This is only valid for `EcdsaPrivateKey` and `EddsaPrivateKey` values. The share for the i-th party is the
+    /// evaluation of the polynomial at `i + 1`.
+    ///
+    /// @param {number} threshold - The number of shares needed to reconstruct the private key, at least 2
+    /// @param {number} parties - The number of shares to produce
+    /// @return {Array<object>} The key shares, in the format used by `EncryptedNadaValues.to_js_object`, including
+    ///   their `vssSetup`
+    /// @throws {Error} if the value is not a private key, or the threshold is not between 2 and the number of parties.
+    ///
+    /// @example
+    /// const shares = NadaValue.new_ecdsa_private_key(privateKey).split_private_key(2, 3);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn split_private_key(&self, threshold: u16, parties: u16) -> JsResult<Vec<JsValue>> {
+        use nillion_client_core::values::NadaValue as CoreNadaValue;
+        let shares: Vec<_> = match &self.0 {
+            CoreNadaValue::EcdsaPrivateKey(private_key) => {
+                let bytes = Zeroizing::new(private_key.clone().to_be_bytes());
+                let secret = SecretScalar::<Secp256k1>::from_be_bytes(&bytes)
+                    .map_err(|_| ValueError::new_err("Invalid ecdsa private key"))?;
+                split(&secret, threshold, parties)?.into_iter().map(CoreNadaValue::new_ecdsa_private_key).collect()
+            }
+            CoreNadaValue::EddsaPrivateKey(private_key) => {
+                let bytes = Zeroizing::new(private_key.clone().to_le_bytes());
+                let secret = SecretScalar::<Ed25519>::from_le_bytes(&bytes)
+                    .map_err(|_| ValueError::new_err("Invalid eddsa private key"))?;
+                split(&secret, threshold, parties)?.into_iter().map(CoreNadaValue::new_eddsa_private_key).collect()
+            }
+            _ => return Err(ValueError::new_err("value is not a private key")),
+        };
+        shares.iter().map(|share| EncryptedNadaValues::value_to_js_object(share).map(JsValue::from)).collect()
+    }
+}
+
+#[wasm_bindgen]
+impl NadaValue {
+    /// Recover the cleartext private key out of its key shares.
+    ///
+    /// This reveals the private key to whoever runs it, it is meant to hand a key back to its owner. Shares with a
+    /// VSS setup need `minSigners` distinct shares, while additive shares without one need the share of every party.
+    /// The recovered key is checked against the shares' `sharedPublicKey`.
+    ///
+    /// @param {Array<object>} shares - The key shares, in the format used by `EncryptedNadaValues.to_js_object`
+    /// @return {NadaValue} The `EcdsaPrivateKey` or `EddsaPrivateKey` value
+    /// @throws {Error} if the shares are malformed, belong to different keys or there aren't enough of them.
+    ///
+    /// @example
+    /// const privateKey = NadaValue.recover_private_key_from_key_shares(shares.map((share) => share.my_key));
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn recover_private_key_from_key_shares(shares: Vec<JsValue>) -> JsResult<NadaValue> {
+        use nillion_client_core::values::NadaValue as CoreNadaValue;
+        let mut type_names = shares.iter().map(|share| {
+            js_sys::Reflect::get(share, &JsValue::from("type")).map(|type_name| type_name.as_string()).ok().flatten()
+        });
+        let type_name = type_names.next().flatten().ok_or_else(|| ValueError::new_err("no key shares provided"))?;
+        if type_names.any(|other| other.as_deref() != Some(type_name.as_str())) {
+            return Err(ValueError::new_err("key shares must all have the same type"));
+        }
+        let error = |e: String| ValueError::new_err(&format!("failed to recover private key: {e}"));
+        match type_name.as_str() {
+            "EcdsaPrivateKey" => {
+                let shares = shares
+                    .iter()
+                    .map(EncryptedNadaValues::json_to_private_key::<Secp256k1>)
+                    .collect::<JsResult<Vec<_>>>()?;
+                let secret = recover(&shares).map_err(error)?;
+                let bytes = Zeroizing::new(scalar(&secret).to_be_bytes().to_vec());
+                let private_key = ThresholdPrivateKey::from_be_bytes(bytes.as_slice())
+                    .map_err(|e| ValueError::new_err(&format!("Invalid ecdsa private key: {e}")))?;
+                Ok(Self(CoreNadaValue::new_ecdsa_private_key(private_key)))
+            }
+            "EddsaPrivateKey" => {
+                let shares = shares
+                    .iter()
+                    .map(EncryptedNadaValues::json_to_private_key::<Ed25519>)
+                    .collect::<JsResult<Vec<_>>>()?;
+                let secret = recover(&shares).map_err(error)?;
+                let bytes = Zeroizing::new(scalar(&secret).to_le_bytes().to_vec());
+                let private_key = ThresholdPrivateKey::from_le_bytes(bytes.as_slice())
+                    .map_err(|e| ValueError::new_err(&format!("Invalid eddsa private key: {e}")))?;
+                Ok(Self(CoreNadaValue::new_eddsa_private_key(private_key)))
+            }
+            _ => Err(ValueError::new_err(&format!("{type_name} values are not private key shares"))),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl EncryptedNadaValues {
+    /// Validate a private key share before trusting it.
+    ///
+    /// This checks that the share's secret matches its entry in `publicShares`, that the public shares are
+    /// consistent with `sharedPublicKey`, either through the VSS setup or as additive shares when there is none, and
+    /// that `sharedPublicKey` is the expected public key.
+    ///
+    /// @param {string} name - The name of the private key share
+    /// @param {NadaValue} public_key - The expected `EcdsaPublicKey` or `EddsaPublicKey` value
+    /// @throws {Error} if there is no private key share with that name or the share is not valid.
+    ///
+    /// @example
+    /// shares.validate_private_key_share("my_key", publicKey);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn validate_private_key_share(&self, name: &str, public_key: &NadaValue) -> JsResult<()> {
+        use nillion_client_core::values::NadaValue as CoreNadaValue;
+        let public_key = match &public_key.0 {
+            CoreNadaValue::EcdsaPublicKey(public_key) => public_key.0.to_vec(),
+            CoreNadaValue::EddsaPublicKey(public_key) => public_key.to_vec(),
+            _ => return Err(ValueError::new_err("value is not a public key")),
+        };
+        let result = match self.0.get(name) {
+            Some(CoreNadaValue::EcdsaPrivateKey(share)) => validate(share, &public_key),
+            Some(CoreNadaValue::EddsaPrivateKey(share)) => validate(share, &public_key),
+            Some(_) => return Err(ValueError::new_err(&format!("value {name} is not a private key share"))),
+            None => return Err(ValueError::new_err(&format!("value {name} not found"))),
+        };
+        result.map_err(|e| ValueError::new_err(&format!("invalid private key share {name}: {e}")))
+    }
+}
+
+/// Split a secret into shares of a random polynomial of degree `threshold - 1`.
+fn split<E: Curve>(
+    secret: &SecretScalar<E>,
+    threshold: u16,
+    parties: u16,
+) -> JsResult<Vec<ThresholdPrivateKeyShare<E>>> {
+    if threshold < 2 || threshold > parties {
+        return Err(ValueError::new_err("threshold must be between 2 and the number of parties"));
+    }
+    let shared_public_key = NonZero::from_point(derive_public_key(secret))
+        .ok_or_else(|| ValueError::new_err("private key can't be zero"))?;
+
+    // The constant term is the secret and the remaining coefficients are random.
+    let mut coefficients = vec![secret.clone()];
+    for _ in 1..threshold {
+        coefficients.push(random_scalar::<E>()?.into());
+    }
+    let indexes = (1..=parties)
+        .map(|index| NonZero::from_scalar(Scalar::from(index)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| ValueError::new_err("key share index can't be zero"))?;
+    let secret_shares = indexes.iter().map(|index| evaluate(&coefficients, index)).collect::<JsResult<Vec<_>>>()?;
+    let public_shares = secret_shares
+        .iter()
+        .map(|share| NonZero::from_point(derive_public_key(share)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| ValueError::new_err("public share can't be zero"))?;
+
+    let vss_setup = VssSetup { min_signers: threshold, I: indexes };
+    secret_shares
+        .into_iter()
+        .enumerate()
+        .map(|(i, x)| {
+            let i = u16::try_from(i).map_err(|_| ValueError::new_err("too many parties"))?;
+            EncryptedNadaValues::build_private_key(
+                i,
+                x,
+                shared_public_key,
+                public_shares.clone(),
+                Some(vss_setup.clone()),
+            )
+        })
+        .collect()
+}
+
+/// Recover the secret behind a set of key shares and check it against their shared public key.
+fn recover<E: Curve>(shares: &[ThresholdPrivateKeyShare<E>]) -> Result<SecretScalar<E>, String> {
+    let key_info = &shares.first().ok_or_else(|| "no key shares provided".to_string())?.as_inner().key_info;
+    let mut distinct = Vec::new();
+    for share in shares {
+        let share = share.as_inner();
+        if share.key_info.shared_public_key != key_info.shared_public_key
+            || share.key_info.public_shares != key_info.public_shares
+        {
+            return Err("key shares belong to different keys".to_string());
+        }
+        if !distinct.iter().any(|other: &&_| other.i == share.i) {
+            distinct.push(share);
+        }
+    }
+    let mut secret = match &key_info.vss_setup {
+        Some(vss_setup) => {
+            let threshold = usize::from(vss_setup.min_signers);
+            let selected = distinct.get(..threshold).ok_or_else(|| {
+                format!("{threshold} distinct key shares are needed but only {} were provided", distinct.len())
+            })?;
+            let indexes = selected
+                .iter()
+                .map(|share| vss_setup.I.get(usize::from(share.i)).copied())
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| "share index is out of range".to_string())?;
+            let coefficients = lagrange_coefficients(&indexes, &Scalar::zero())?;
+            selected
+                .iter()
+                .zip(coefficients)
+                .fold(Scalar::zero(), |secret, (share, coefficient)| add(secret, mul(*scalar(&share.x), coefficient)))
+        }
+        None => {
+            let parties = key_info.public_shares.len();
+            if distinct.len() != parties {
+                return Err(format!(
+                    "the shares of all {parties} parties are needed but only {} were provided",
+                    distinct.len()
+                ));
+            }
+            distinct.iter().fold(Scalar::zero(), |secret, share| add(secret, *scalar(&share.x)))
+        }
+    };
+    let secret = SecretScalar::new(&mut secret);
+    if derive_public_key(&secret) != *key_info.shared_public_key {
+        return Err("recovered private key doesn't match the shared public key".to_string());
+    }
+    Ok(secret)
+}
+
+/// Evaluate a polynomial with secret coefficients, lowest degree first.
+fn evaluate<E: Curve>(coefficients: &[SecretScalar<E>], x: &Scalar<E>) -> JsResult<NonZero<SecretScalar<E>>> {
+    let mut value = Scalar::zero();
+    for coefficient in coefficients.iter().rev() {
+        value = add(mul(value, *x), *scalar(coefficient));
+    }
+    NonZero::from_secret_scalar(SecretScalar::new(&mut value))
+        .ok_or_else(|| ValueError::new_err("key share can't be zero"))
+}
+
+/// Check a key share is consistent with its public shares and the expected public key.
+fn validate<E: Curve>(share: &ThresholdPrivateKeyShare<E>, public_key: &[u8]) -> Result<(), String> {
+    let share = share.as_inner();
+    let key_info = &share.key_info;
+    if key_info.shared_public_key.to_bytes(true).as_bytes() != public_key {
+        return Err("shared public key doesn't match the expected public key".to_string());
+    }
+    let public_share =
+        key_info.public_shares.get(usize::from(share.i)).ok_or_else(|| "share index is out of range".to_string())?;
+    if derive_public_key(&share.x) != **public_share {
+        return Err("secret share doesn't match its public share".to_string());
+    }
+    match &key_info.vss_setup {
+        Some(vss_setup) => {
+            let threshold = usize::from(vss_setup.min_signers);
+            if vss_setup.I.len() != key_info.public_shares.len() {
+                return Err("VSS setup must have an index per public share".to_string());
+            }
+            let (Some(indexes), Some(points)) = (vss_setup.I.get(..threshold), key_info.public_shares.get(..threshold))
+            else {
+                return Err("VSS threshold is larger than the number of shares".to_string());
+            };
+            if interpolate(indexes, points, &Scalar::zero())? != *key_info.shared_public_key {
+                return Err("public shares don't match the shared public key".to_string());
+            }
+            let remaining = vss_setup.I.iter().zip(&key_info.public_shares).skip(threshold);
+            for (index, point) in remaining {
+                if interpolate(indexes, points, index)? != **point {
+                    let degree = threshold.saturating_sub(1);
+                    return Err(format!("public shares don't lie on a polynomial of degree {degree}"));
+                }
+            }
+        }
+        None => {
+            let sum = key_info.public_shares.iter().fold(Point::zero(), |sum, point| add_points(sum, **point));
+            if sum != *key_info.shared_public_key {
+                return Err("public shares don't add up to the shared public key".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Evaluate at `x` the polynomial in the exponent going through some public shares, using Lagrange interpolation.
+pub(crate) fn interpolate<E: Curve>(
+    indexes: &[NonZero<Scalar<E>>],
+    points: &[NonZero<Point<E>>],
+    x: &Scalar<E>,
+) -> Result<Point<E>, String> {
+    let coefficients = lagrange_coefficients(indexes, x)?;
+    Ok(points
+        .iter()
+        .zip(coefficients)
+        .fold(Point::zero(), |result, (point, coefficient)| add_points(result, mul_point(**point, coefficient))))
+}
+
+/// Compute the Lagrange coefficients to evaluate at `x` the polynomial going through some indexes.
+pub(crate) fn lagrange_coefficients<E: Curve>(
+    indexes: &[NonZero<Scalar<E>>],
+    x: &Scalar<E>,
+) -> Result<Vec<Scalar<E>>, String> {
+    let mut coefficients = Vec::with_capacity(indexes.len());
+    for (j, index) in indexes.iter().enumerate() {
+        let mut coefficient = Scalar::one();
+        for (m, other) in indexes.iter().enumerate() {
+            if m == j {
+                continue;
+            }
+            let denominator =
+                sub(**index, **other).invert().ok_or_else(|| "key share indexes must be distinct".to_string())?;
+            coefficient = mul(mul(coefficient, sub(*x, **other)), denominator);
+        }
+        coefficients.push(coefficient);
+    }
+    Ok(coefficients)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::values::{NadaValues, PartyId, SecretMasker};
+    use js_sys::{Object, Reflect};
+    use wasm_bindgen_test::*;
+
+    fn make_masker() -> Result<SecretMasker, JsValue> {
+        let parties = vec![PartyId::new(vec![1]), PartyId::new(vec![2]), PartyId::new(vec![3])];
+        Ok(SecretMasker::new_64_bit_safe_prime(1, parties)?)
+    }
+
+    fn to_values(name: &str, share: &JsValue) -> Result<EncryptedNadaValues, JsValue> {
+        let masker = make_masker()?;
+        let object = Object::new();
+        Reflect::set(&object, &JsValue::from(name), share)?;
+        Ok(EncryptedNadaValues::from_js_object(&object, masker.modulo())?)
+    }
+
+    #[wasm_bindgen_test]
+    fn split_ecdsa_private_key() -> Result<(), JsValue> {
+        let private_key = NadaValue::new_ecdsa_private_key(vec![7; 32])?;
+        let public_key = private_key.public_key()?;
+        let shares = private_key.split_private_key(2, 3)?;
+        assert_eq!(shares.len(), 3);
+        for (i, share) in shares.iter().enumerate() {
+            assert_eq!(Reflect::get(share, &"i".into())?.as_string(), Some(i.to_string()));
+            let vss_setup = Reflect::get(share, &"vssSetup".into())?;
+            assert_eq!(Reflect::get(&vss_setup, &"minSigners".into())?.as_string(), Some("2".to_string()));
+            assert_eq!(js_sys::Array::from(&Reflect::get(&vss_setup, &"indexes".into())?).length(), 3);
+
+            let values = to_values("key", share)?;
+            values.validate_private_key_share("key", &public_key)?;
+            // The VSS setup survives a round trip through the JSON format.
+            assert_eq!(to_values("key", &Reflect::get(&values.to_js_object()?, &"key".into())?)?, values);
+        }
+
+        let other_public_key = NadaValue::new_ecdsa_private_key(vec![8; 32])?.public_key()?;
+        let values = to_values("key", shares.first().ok_or("missing share")?)?;
+        assert!(values.validate_private_key_share("key", &other_public_key).is_err());
+        assert!(values.validate_private_key_share("other", &public_key).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn private_key_share_proto() -> Result<(), JsValue> {
+        let private_key = NadaValue::new_ecdsa_private_key(vec![7; 32])?;
+        for share in private_key.split_private_key(2, 3)? {
+            // The protobuf message can't carry the VSS setup so these shares are rejected rather than mangled.
+            let values = to_values("key", &share)?;
+            assert!(values.to_proto_values().is_err());
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn split_eddsa_private_key() -> Result<(), JsValue> {
+        let private_key = NadaValue::new_eddsa_private_key(vec![3; 32])?;
+        let public_key = private_key.public_key()?;
+        for share in private_key.split_private_key(3, 5)? {
+            to_values("key", &share)?.validate_private_key_share("key", &public_key)?;
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn tampered_shares() -> Result<(), JsValue> {
+        let private_key = NadaValue::new_ecdsa_private_key(vec![7; 32])?;
+        let shares = private_key.split_private_key(2, 3)?;
+        let (Some(first), Some(second)) = (shares.first(), shares.get(1)) else {
+            return Err("missing shares".into());
+        };
+        // A share whose secret doesn't match its public share is rejected.
+        Reflect::set(first, &"x".into(), &Reflect::get(second, &"x".into())?)?;
+        assert!(to_values("key", first).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn invalid_split() -> Result<(), JsValue> {
+        let private_key = NadaValue::new_ecdsa_private_key(vec![7; 32])?;
+        assert!(private_key.split_private_key(1, 3).is_err());
+        assert!(private_key.split_private_key(4, 3).is_err());
+        assert!(NadaValue::new_secret_integer("42")?.split_private_key(2, 3).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn recover_vss_private_keys() -> Result<(), JsValue> {
+        let private_key = NadaValue::new_ecdsa_private_key(vec![7; 32])?;
+        let shares = private_key.split_private_key(2, 3)?;
+        let (Some(first), Some(second), Some(third)) = (shares.first(), shares.get(1), shares.get(2)) else {
+            return Err("missing shares".into());
+        };
+        let recovered = NadaValue::recover_private_key_from_key_shares(vec![third.clone(), first.clone()])?;
+        assert_eq!(recovered.into_inner(), private_key.into_inner());
+        assert!(NadaValue::recover_private_key_from_key_shares(vec![second.clone()]).is_err());
+        assert!(NadaValue::recover_private_key_from_key_shares(vec![second.clone(), second.clone()]).is_err());
+
+        let private_key = NadaValue::new_eddsa_private_key(vec![3; 32])?;
+        let mut shares = private_key.split_private_key(3, 5)?;
+        shares.reverse();
+        let recovered = NadaValue::recover_private_key_from_key_shares(shares)?;
+        assert_eq!(recovered.into_inner(), private_key.into_inner());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn recover_additive_private_key() -> Result<(), JsValue> {
+        let private_key = NadaValue::new_ecdsa_private_key(vec![9; 32])?;
+        let mut values = NadaValues::new()?;
+        values.insert("key".into(), &private_key);
+        let shares = make_masker()?
+            .mask(values)?
+            .into_iter()
+            .map(|party_shares| Reflect::get(&party_shares.shares().to_js_object()?, &"key".into()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let recovered = NadaValue::recover_private_key_from_key_shares(shares.clone())?;
+        assert_eq!(recovered.into_inner(), private_key.into_inner());
+        let partial = shares.get(1..).unwrap_or_default().to_vec();
+        assert!(NadaValue::recover_private_key_from_key_shares(partial).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn recover_mismatched_shares() -> Result<(), JsValue> {
+        let first = NadaValue::new_ecdsa_private_key(vec![7; 32])?.split_private_key(2, 3)?;
+        let second = NadaValue::new_ecdsa_private_key(vec![8; 32])?.split_private_key(2, 3)?;
+        let eddsa = NadaValue::new_eddsa_private_key(vec![3; 32])?.split_private_key(2, 3)?;
+        let (Some(first), Some(second), Some(eddsa)) = (first.first(), second.get(1), eddsa.get(1)) else {
+            return Err("missing shares".into());
+        };
+        assert!(NadaValue::recover_private_key_from_key_shares(vec![first.clone(), second.clone()]).is_err());
+        assert!(NadaValue::recover_private_key_from_key_shares(vec![first.clone(), eddsa.clone()]).is_err());
+        assert!(NadaValue::recover_private_key_from_key_shares(vec![]).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn interpolation() -> Result<(), JsValue> {
+        // f(x) = 5 + 3x, evaluated in the exponent at 1, 2 and 3.
+        let point = |value: u64| -> Result<NonZero<Point<Secp256k1>>, JsValue> {
+            NonZero::from_point(mul_generator(Scalar::from(value))).ok_or_else(|| "zero point".into())
+        };
+        let index = |value: u64| NonZero::from_scalar(Scalar::<Secp256k1>::from(value)).ok_or("zero index");
+        let indexes = [index(1)?, index(2)?];
+        let points = [point(8)?, point(11)?];
+        assert_eq!(interpolate(&indexes, &points, &Scalar::zero())?, *point(5)?);
+        assert_eq!(interpolate(&indexes, &points, &Scalar::from(3u64))?, *point(14)?);
+        assert!(interpolate(&[index(1)?, index(1)?], &points, &Scalar::zero()).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,292 @@
+//! Private key generation and public key derivation.
+use crate::{
+    errors::{JsResult, ValueError},
+    values::{EncryptedNadaValues, NadaValue},
+};
+use nillion_client_core::{
+    generic_ec::{
+        curves::{Ed25519, Secp256k1},
+        Curve, NonZero, Point, Scalar, SecretScalar,
+    },
+    privatekey::ThresholdPrivateKey,
+};
+use wasm_bindgen::prelude::wasm_bindgen;
+use zeroize::Zeroizing;
+
+/// A private key along with its public key.
+#[wasm_bindgen]
+pub struct KeyPair {
+    private_key: NadaValue,
+    public_key: NadaValue,
+}
+
+#[wasm_bindgen]
+impl KeyPair {
+    /// The private key value.
+    #[wasm_bindgen(getter)]
+    pub fn private_key(&self) -> NadaValue {
+        NadaValue(self.private_key.0.clone())
+    }
+
+    /// The public key value.
+    #[wasm_bindgen(getter)]
+    pub fn public_key(&self) -> NadaValue {
+        NadaValue(self.public_key.0.clone())
+    }
+}
+
+#[wasm_bindgen]
+impl NadaValue {
+    /// Generate a new random ecdsa private key.
+    ///
+    /// The key is generated within the wasm module so the cleartext key is never exposed to javascript.
+    ///
+    /// @return {KeyPair} The `EcdsaPrivateKey` value along with its `EcdsaPublicKey` value
+    ///
+    /// @example
+    /// const { private_key, public_key } = NadaValue.generate_ecdsa_private_key();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn generate_ecdsa_private_key() -> JsResult<KeyPair> {
+        let secret = random_scalar::<Secp256k1>()?;
+        let secret: &SecretScalar<Secp256k1> = secret.as_ref();
+        let bytes = Zeroizing::new(scalar(secret).to_be_bytes().to_vec());
+        let private_key = ThresholdPrivateKey::from_be_bytes(&bytes)
+            .map_err(|e| ValueError::new_err(&format!("Invalid ecdsa private key: {e}")))?;
+        let public_key = derive_public_key(secret);
+        Ok(KeyPair {
+            private_key: NadaValue(nillion_client_core::values::NadaValue::new_ecdsa_private_key(private_key)),
+            public_key: NadaValue::new_ecdsa_public_key(public_key.to_bytes(true).to_vec())?,
+        })
+    }
+
+    /// Generate a new random eddsa private key.
+    ///
+    /// The key is generated within the wasm module so the cleartext key is never exposed to javascript.
+    ///
+    /// @return {KeyPair} The `EddsaPrivateKey` value along with its `EddsaPublicKey` value
+    ///
+    /// @example
+    /// const { private_key, public_key } = NadaValue.generate_eddsa_private_key();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn generate_eddsa_private_key() -> JsResult<KeyPair> {
+        let secret = random_scalar::<Ed25519>()?;
+        let secret: &SecretScalar<Ed25519> = secret.as_ref();
+        let bytes = Zeroizing::new(scalar(secret).to_le_bytes().to_vec());
+        let private_key = ThresholdPrivateKey::from_le_bytes(&bytes)
+            .map_err(|e| ValueError::new_err(&format!("Invalid eddsa private key: {e}")))?;
+        let public_key = derive_public_key(secret);
+        Ok(KeyPair {
+            private_key: NadaValue(nillion_client_core::values::NadaValue::new_eddsa_private_key(private_key)),
+            public_key: NadaValue::new_eddsa_public_key(public_key.to_bytes(true).to_vec())?,
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl NadaValue {
+    /// Derive the public key for a private key value.
+    ///
+    /// This is only valid for `EcdsaPrivateKey` and `EddsaPrivateKey` values.
+    ///
+    /// @return {NadaValue} The `EcdsaPublicKey` or `EddsaPublicKey` value for this private key
+    /// @throws {Error} if the value is not a private key.
+    ///
+    /// @example
+    /// const publicKey = NadaValue.new_ecdsa_private_key(privateKey).public_key();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn public_key(&self) -> JsResult<NadaValue> {
+        match &self.0 {
+            nillion_client_core::values::NadaValue::EcdsaPrivateKey(private_key) => {
+                let bytes = Zeroizing::new(private_key.clone().to_be_bytes());
+                let secret = SecretScalar::<Secp256k1>::from_be_bytes(&bytes)
+                    .map_err(|_| ValueError::new_err("Invalid ecdsa private key"))?;
+                NadaValue::new_ecdsa_public_key(derive_public_key(&secret).to_bytes(true).to_vec())
+            }
+            nillion_client_core::values::NadaValue::EddsaPrivateKey(private_key) => {
+                let bytes = Zeroizing::new(private_key.clone().to_le_bytes());
+                let secret = SecretScalar::<Ed25519>::from_le_bytes(&bytes)
+                    .map_err(|_| ValueError::new_err("Invalid eddsa private key"))?;
+                NadaValue::new_eddsa_public_key(derive_public_key(&secret).to_bytes(true).to_vec())
+            }
+            _ => Err(ValueError::new_err("value is not a private key")),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl EncryptedNadaValues {
+    /// Get the public key shared by all the shares of a private key.
+    ///
+    /// This is only valid for ecdsa and eddsa private key shares, such as the ones in retrieved values.
+    ///
+    /// @param {string} name - The name of the private key share
+    /// @return {NadaValue} The `EcdsaPublicKey` or `EddsaPublicKey` value for the private key
+    /// @throws {Error} if there is no private key share with that name.
+    ///
+    /// @example
+    /// const publicKey = shares.shared_public_key("my_key");
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn shared_public_key(&self, name: &str) -> JsResult<NadaValue> {
+        match self.0.get(name) {
+            Some(nillion_client_core::values::NadaValue::EcdsaPrivateKey(share)) => {
+                let public_key = &share.as_inner().key_info.shared_public_key;
+                NadaValue::new_ecdsa_public_key(public_key.to_bytes(true).to_vec())
+            }
+            Some(nillion_client_core::values::NadaValue::EddsaPrivateKey(share)) => {
+                let public_key = &share.as_inner().key_info.shared_public_key;
+                NadaValue::new_eddsa_public_key(public_key.to_bytes(true).to_vec())
+            }
+            Some(_) => Err(ValueError::new_err(&format!("value {name} is not a private key share"))),
+            None => Err(ValueError::new_err(&format!("value {name} not found"))),
+        }
+    }
+}
+
+/// Generate a random non zero secret scalar using the platform's random number generator.
+///
+/// 64 random bytes are reduced modulo the curve order so the bias is negligible.
+pub(crate) fn random_scalar<E: Curve>() -> JsResult<NonZero<SecretScalar<E>>> {
+    loop {
+        let mut bytes = Zeroizing::new([0; 64]);
+        getrandom::getrandom(bytes.as_mut())
+            .map_err(|e| ValueError::new_err(&format!("Failed to generate random bytes: {e}")))?;
+        let secret = SecretScalar::new(&mut Scalar::from_be_bytes_mod_order(bytes.as_ref()));
+        // Zero is astronomically unlikely, but it's not a valid key.
+        if let Some(secret) = NonZero::from_secret_scalar(secret) {
+            return Ok(secret);
+        }
+    }
+}
+
+/// The scalar behind a secret scalar.
+pub(crate) fn scalar<E: Curve>(secret: &SecretScalar<E>) -> &Scalar<E> {
+    secret.as_ref()
+}
+
+/// Derive the public key for a private key.
+pub(crate) fn derive_public_key<E: Curve>(secret: &SecretScalar<E>) -> Point<E> {
+    arithmetic::mul_generator_secret(secret)
+}
+
+/// Scalar and point arithmetic.
+///
+/// Operations on scalars and points are modular so they can't overflow, but `clippy::arithmetic_side_effects` can't
+/// tell them apart from integer arithmetic. This is the one place the lint is allowed for them, everything else goes
+/// through these helpers.
+#[allow(clippy::arithmetic_side_effects)]
+pub(crate) mod arithmetic {
+    use nillion_client_core::generic_ec::{Curve, Point, Scalar, SecretScalar};
+
+    /// `a + b`
+    pub(crate) fn add<E: Curve>(a: Scalar<E>, b: Scalar<E>) -> Scalar<E> {
+        a + b
+    }
+
+    /// `a - b`
+    pub(crate) fn sub<E: Curve>(a: Scalar<E>, b: Scalar<E>) -> Scalar<E> {
+        a - b
+    }
+
+    /// `a * b`
+    pub(crate) fn mul<E: Curve>(a: Scalar<E>, b: Scalar<E>) -> Scalar<E> {
+        a * b
+    }
+
+    /// `-a`
+    pub(crate) fn neg<E: Curve>(a: Scalar<E>) -> Scalar<E> {
+        -a
+    }
+
+    /// `a + b` for points.
+    pub(crate) fn add_points<E: Curve>(a: Point<E>, b: Point<E>) -> Point<E> {
+        a + b
+    }
+
+    /// `a - b` for points.
+    pub(crate) fn sub_points<E: Curve>(a: Point<E>, b: Point<E>) -> Point<E> {
+        a - b
+    }
+
+    /// `point * scalar`
+    pub(crate) fn mul_point<E: Curve>(point: Point<E>, scalar: Scalar<E>) -> Point<E> {
+        point * scalar
+    }
+
+    /// `G * scalar`
+    pub(crate) fn mul_generator<E: Curve>(scalar: Scalar<E>) -> Point<E> {
+        Point::generator() * scalar
+    }
+
+    /// `G * secret`, using the secret scalar directly so no copy of it is left behind.
+    pub(crate) fn mul_generator_secret<E: Curve>(secret: &SecretScalar<E>) -> Point<E> {
+        Point::generator() * secret
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::values::{NadaValues, PartyId, SecretMasker};
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn generate_ecdsa_private_key() -> Result<(), JsValue> {
+        let key_pair = NadaValue::generate_ecdsa_private_key()?;
+        assert_eq!(key_pair.private_key().type_name()?, "EcdsaPrivateKey");
+        assert_eq!(key_pair.public_key().type_name()?, "EcdsaPublicKey");
+
+        let private_key = key_pair.private_key().to_byte_array()?.to_vec();
+        let secret = SecretScalar::<Secp256k1>::from_be_bytes(&private_key).map_err(|_| "invalid private key")?;
+        let public_key = derive_public_key(&secret);
+        assert_eq!(key_pair.public_key().to_byte_array()?.to_vec(), public_key.to_bytes(true).to_vec());
+
+        let other = NadaValue::generate_ecdsa_private_key()?;
+        assert_ne!(other.public_key(), key_pair.public_key());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn generate_eddsa_private_key() -> Result<(), JsValue> {
+        let key_pair = NadaValue::generate_eddsa_private_key()?;
+        assert_eq!(key_pair.private_key().type_name()?, "EddsaPrivateKey");
+        assert_eq!(key_pair.public_key().type_name()?, "EddsaPublicKey");
+
+        let private_key = key_pair.private_key().to_byte_array()?.to_vec();
+        let secret = SecretScalar::<Ed25519>::from_le_bytes(&private_key).map_err(|_| "invalid private key")?;
+        let public_key = derive_public_key(&secret);
+        assert_eq!(key_pair.public_key().to_byte_array()?.to_vec(), public_key.to_bytes(true).to_vec());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn public_key() -> Result<(), JsValue> {
+        let key_pair = NadaValue::generate_ecdsa_private_key()?;
+        assert_eq!(key_pair.private_key().public_key()?, key_pair.public_key());
+
+        let key_pair = NadaValue::generate_eddsa_private_key()?;
+        assert_eq!(key_pair.private_key().public_key()?, key_pair.public_key());
+
+        assert!(key_pair.public_key().public_key().is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn shared_public_key() -> Result<(), JsValue> {
+        let masker = SecretMasker::new_64_bit_safe_prime(
+            1,
+            vec![PartyId::new(vec![1]), PartyId::new(vec![2]), PartyId::new(vec![3])],
+        )?;
+        let key_pair = NadaValue::generate_ecdsa_private_key()?;
+        let mut values = NadaValues::new()?;
+        values.insert("key".to_string(), &key_pair.private_key());
+        values.insert("secret".to_string(), &NadaValue::new_secret_integer("42")?);
+        for party_shares in masker.mask(values)? {
+            let shares = party_shares.shares();
+            assert_eq!(shares.shared_public_key("key")?, key_pair.public_key());
+            assert!(shares.shared_public_key("secret").is_err());
+            assert!(shares.shared_public_key("missing").is_err());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,329 @@
+//! Ethereum V3 keystore encryption, as described in the Web3 Secret Storage definition.
+use crate::{
+    errors::{JsResult, ValueError},
+    ethereum::address_from_public_key,
+    key_formats::{decode_hex, ecdsa_private_key},
+    keys::derive_public_key,
+    values::NadaValue,
+};
+use aes::{
+    cipher::{KeyIvInit, StreamCipher},
+    Aes128,
+};
+use js_sys::JsString;
+use nillion_client_core::generic_ec::{curves::Secp256k1, SecretScalar};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+use subtle::ConstantTimeEq;
+use wasm_bindgen::prelude::wasm_bindgen;
+use zeroize::Zeroizing;
+
+type Aes128Ctr = ctr::Ctr128BE<Aes128>;
+
+/// The only cipher supported by V3 keystores.
+const CIPHER: &str = "aes-128-ctr";
+
+/// The scrypt parameters used when encrypting keystores, the same "light" parameters geth uses.
+const SCRYPT_N: u64 = 4096;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 6;
+
+/// The largest scrypt parameter n accepted when decrypting keystores, four times what geth uses for standard keystores.
+const MAX_SCRYPT_N: u64 = 1 << 20;
+
+/// The largest product of the scrypt parameters r and p accepted when decrypting keystores.
+const MAX_SCRYPT_R_P: u64 = 64;
+
+/// The most memory, `128 * n * r` bytes, scrypt may use when decrypting keystores.
+const MAX_SCRYPT_MEMORY: u64 = 1 << 30;
+
+/// The largest pbkdf2 iteration count accepted when decrypting keystores.
+const MAX_PBKDF2_C: u32 = 10_000_000;
+
+/// The length of the key derived from the password.
+const DERIVED_KEY_LENGTH: usize = 32;
+
+#[wasm_bindgen]
+impl NadaValue {
+    /// Create a new ecdsa private key by decrypting an Ethereum V3 keystore.
+    ///
+    /// Both the `scrypt` and `pbkdf2` key derivation functions are supported.
+    ///
+    /// @param {string} keystore - The keystore JSON
+    /// @param {string} password - The password the keystore is encrypted with
+    /// @return {NadaValue} The `EcdsaPrivateKey` value
+    /// @throws {Error} if the keystore is malformed or the password is wrong.
+    ///
+    /// @example
+    /// const value = NadaValue.new_ecdsa_private_key_from_ethereum_keystore(keystore, "my password");
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn new_ecdsa_private_key_from_ethereum_keystore(keystore: &str, password: &str) -> JsResult<NadaValue> {
+        let keystore: Keystore =
+            serde_json::from_str(keystore).map_err(|e| ValueError::new_err(&format!("Invalid keystore: {e}")))?;
+        if keystore.version != 3 {
+            return Err(ValueError::new_err(&format!("Unsupported keystore version {}", keystore.version)));
+        }
+        let crypto = keystore.crypto;
+        if crypto.cipher != CIPHER {
+            return Err(ValueError::new_err(&format!("Unsupported keystore cipher {}", crypto.cipher)));
+        }
+        let key = derive_key(&crypto.kdf, crypto.kdfparams, password)?;
+        let mut private_key = decode_hex(&crypto.ciphertext)?;
+        if !bool::from(mac(&key, &private_key)?.ct_eq(&decode_hex(&crypto.mac)?)) {
+            return Err(ValueError::new_err("Invalid keystore password"));
+        }
+        apply_cipher(&key, &decode_hex(&crypto.cipherparams.iv)?, &mut private_key)?;
+        ecdsa_private_key(&private_key)
+    }
+
+    /// Encrypt an ecdsa private key into an Ethereum V3 keystore.
+    ///
+    /// The key is encrypted using scrypt with the same parameters geth uses for its "light" keystores.
+    ///
+    /// @param {string} password - The password to encrypt the keystore with
+    /// @return {string} The keystore JSON
+    /// @throws {Error} if the value is not an ecdsa private key.
+    ///
+    /// @example
+    /// const keystore = value.to_ethereum_keystore("my password");
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_ethereum_keystore(&self, password: &str) -> JsResult<JsString> {
+        let secret_key = self.ecdsa_secret_key("an Ethereum keystore")?;
+        let private_key = Zeroizing::new(secret_key.to_bytes().to_vec());
+        let secret = SecretScalar::<Secp256k1>::from_be_bytes(&private_key)
+            .map_err(|_| ValueError::new_err("Invalid ecdsa private key"))?;
+        let address = address_from_public_key(&derive_public_key(&secret));
+
+        let mut salt = [0; 32];
+        let mut iv = [0; 16];
+        random_bytes(&mut salt)?;
+        random_bytes(&mut iv)?;
+        let params =
+            ScryptParams { dklen: DERIVED_KEY_LENGTH, n: SCRYPT_N, r: SCRYPT_R, p: SCRYPT_P, salt: hex::encode(salt) };
+        let kdfparams = serde_json::to_value(&params)
+            .map_err(|e| ValueError::new_err(&format!("Failed to encode keystore: {e}")))?;
+        let key = derive_key("scrypt", kdfparams.clone(), password)?;
+        let mut ciphertext = private_key.to_vec();
+        apply_cipher(&key, &iv, &mut ciphertext)?;
+
+        let keystore = Keystore {
+            version: 3,
+            id: uuid_v4()?,
+            address: Some(hex::encode(address)),
+            crypto: Crypto {
+                cipher: CIPHER.to_string(),
+                cipherparams: CipherParams { iv: hex::encode(iv) },
+                mac: hex::encode(mac(&key, &ciphertext)?),
+                ciphertext: hex::encode(ciphertext),
+                kdf: "scrypt".to_string(),
+                kdfparams,
+            },
+        };
+        let keystore = serde_json::to_string(&keystore)
+            .map_err(|e| ValueError::new_err(&format!("Failed to encode keystore: {e}")))?;
+        Ok(JsString::from(keystore))
+    }
+}
+
+/// A V3 keystore.
+#[derive(Serialize, Deserialize)]
+struct Keystore {
+    version: u32,
+    id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    // Some wallets capitalize this field.
+    #[serde(alias = "Crypto")]
+    crypto: Crypto,
+}
+
+/// The encrypted private key along with how to decrypt it.
+#[derive(Serialize, Deserialize)]
+struct Crypto {
+    cipher: String,
+    cipherparams: CipherParams,
+    ciphertext: String,
+    kdf: String,
+    kdfparams: Value,
+    mac: String,
+}
+
+/// The parameters of the cipher.
+#[derive(Serialize, Deserialize)]
+struct CipherParams {
+    iv: String,
+}
+
+/// The parameters of the scrypt key derivation function.
+#[derive(Serialize, Deserialize)]
+struct ScryptParams {
+    dklen: usize,
+    n: u64,
+    r: u32,
+    p: u32,
+    salt: String,
+}
+
+/// The parameters of the pbkdf2 key derivation function.
+#[derive(Deserialize)]
+struct Pbkdf2Params {
+    dklen: usize,
+    c: u32,
+    prf: String,
+    salt: String,
+}
+
+/// Derive the key used to encrypt the private key and compute the MAC out of the password.
+fn derive_key(kdf: &str, params: Value, password: &str) -> JsResult<Zeroizing<Vec<u8>>> {
+    let invalid_params = |e: serde_json::Error| ValueError::new_err(&format!("Invalid {kdf} parameters: {e}"));
+    match kdf {
+        "scrypt" => {
+            let ScryptParams { dklen, n, r, p, salt } = serde_json::from_value(params).map_err(invalid_params)?;
+            if !n.is_power_of_two() {
+                return Err(ValueError::new_err("Scrypt parameter n must be a power of two"));
+            }
+            // Untrusted keystores could otherwise make us spend unbounded time and memory.
+            let r_p = u64::from(r).saturating_mul(u64::from(p));
+            let memory = n.saturating_mul(u64::from(r)).saturating_mul(128);
+            if n > MAX_SCRYPT_N || r_p > MAX_SCRYPT_R_P || memory > MAX_SCRYPT_MEMORY {
+                return Err(ValueError::new_err("Scrypt parameters exceed the supported cost"));
+            }
+            let log_n =
+                u8::try_from(n.trailing_zeros()).map_err(|_| ValueError::new_err("Invalid scrypt parameter n"))?;
+            let params = scrypt::Params::new(log_n, r, p, dklen)
+                .map_err(|e| ValueError::new_err(&format!("Invalid scrypt parameters: {e}")))?;
+            let mut key = derived_key_buffer(dklen)?;
+            scrypt::scrypt(password.as_bytes(), &decode_hex(&salt)?, &params, &mut key)
+                .map_err(|e| ValueError::new_err(&format!("Failed to derive key: {e}")))?;
+            Ok(key)
+        }
+        "pbkdf2" => {
+            let Pbkdf2Params { dklen, c, prf, salt } = serde_json::from_value(params).map_err(invalid_params)?;
+            if prf != "hmac-sha256" {
+                return Err(ValueError::new_err(&format!("Unsupported pbkdf2 pseudorandom function {prf}")));
+            }
+            if c > MAX_PBKDF2_C {
+                return Err(ValueError::new_err(&format!("Pbkdf2 iteration count exceeds {MAX_PBKDF2_C}")));
+            }
+            let mut key = derived_key_buffer(dklen)?;
+            pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), &decode_hex(&salt)?, c, &mut key);
+            Ok(key)
+        }
+        _ => Err(ValueError::new_err(&format!("Unsupported keystore key derivation function {kdf}"))),
+    }
+}
+
+/// Allocate the buffer for a derived key, making sure it has the only supported length.
+fn derived_key_buffer(dklen: usize) -> JsResult<Zeroizing<Vec<u8>>> {
+    if dklen != DERIVED_KEY_LENGTH {
+        return Err(ValueError::new_err(&format!("Unsupported derived key length {dklen}")));
+    }
+    Ok(Zeroizing::new(vec![0; dklen]))
+}
+
+/// Compute the MAC of the ciphertext as `keccak256(key[16..32] || ciphertext)`.
+fn mac(key: &[u8], ciphertext: &[u8]) -> JsResult<Vec<u8>> {
+    let mac_key = key.get(16..32).ok_or_else(|| ValueError::new_err("Derived key is too short"))?;
+    let mut hasher = Keccak256::new();
+    hasher.update(mac_key);
+    hasher.update(ciphertext);
+    Ok(hasher.finalize().to_vec())
+}
+
+/// Encrypt or decrypt some data in place using AES-128-CTR keyed with `key[0..16]`.
+fn apply_cipher(key: &[u8], iv: &[u8], data: &mut [u8]) -> JsResult<()> {
+    let key = key.get(..16).ok_or_else(|| ValueError::new_err("Derived key is too short"))?;
+    let mut cipher =
+        Aes128Ctr::new_from_slices(key, iv).map_err(|_| ValueError::new_err("Keystore iv must be 16 bytes long"))?;
+    cipher.apply_keystream(data);
+    Ok(())
+}
+
+/// Fill a buffer with random bytes from the platform's random number generator.
+fn random_bytes(bytes: &mut [u8]) -> JsResult<()> {
+    getrandom::getrandom(bytes).map_err(|e| ValueError::new_err(&format!("Failed to generate random bytes: {e}")))
+}
+
+/// Generate a random version 4 UUID.
+fn uuid_v4() -> JsResult<String> {
+    let mut bytes = [0; 16];
+    random_bytes(&mut bytes)?;
+    // Set the version and variant bits.
+    if let Some(byte) = bytes.get_mut(6) {
+        *byte = (*byte & 0x0f) | 0x40;
+    }
+    if let Some(byte) = bytes.get_mut(8) {
+        *byte = (*byte & 0x3f) | 0x80;
+    }
+    let hex = hex::encode(bytes);
+    let (time_low, rest) = hex.split_at(8);
+    let (time_mid, rest) = rest.split_at(4);
+    let (time_high, rest) = rest.split_at(4);
+    let (clock_seq, node) = rest.split_at(4);
+    Ok(format!("{time_low}-{time_mid}-{time_high}-{clock_seq}-{node}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_test::*;
+
+    // The pbkdf2 test vector from the Web3 Secret Storage definition.
+    const KEYSTORE: &str = r#"{
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+            "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+            "kdf": "pbkdf2",
+            "kdfparams": {
+                "c": 262144,
+                "dklen": 32,
+                "prf": "hmac-sha256",
+                "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+            },
+            "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+    const PRIVATE_KEY: &str = "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d";
+
+    #[wasm_bindgen_test]
+    fn decrypt() -> Result<(), JsValue> {
+        let value = NadaValue::new_ecdsa_private_key_from_ethereum_keystore(KEYSTORE, "testpassword")?;
+        assert_eq!(String::from(value.to_hex()?), PRIVATE_KEY);
+        assert!(NadaValue::new_ecdsa_private_key_from_ethereum_keystore(KEYSTORE, "wrong").is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn round_trip() -> Result<(), JsValue> {
+        let value = NadaValue::new_ecdsa_private_key_from_hex(PRIVATE_KEY)?;
+        let keystore = String::from(value.to_ethereum_keystore("password")?);
+        assert_eq!(NadaValue::new_ecdsa_private_key_from_ethereum_keystore(&keystore, "password")?, value);
+
+        let keystore: Keystore = serde_json::from_str(&keystore).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let public_key = value.public_key()?;
+        let address = public_key.to_ethereum_address()?.to_lowercase();
+        assert_eq!(keystore.address.map(|address| format!("0x{address}")), Some(address));
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn expensive_parameters() -> Result<(), JsValue> {
+        let keystore = KEYSTORE.replace(r#""c": 262144"#, r#""c": 4294967295"#);
+        assert!(NadaValue::new_ecdsa_private_key_from_ethereum_keystore(&keystore, "testpassword").is_err());
+
+        let value = NadaValue::new_ecdsa_private_key_from_hex(PRIVATE_KEY)?;
+        let keystore = String::from(value.to_ethereum_keystore("password")?);
+        for (from, to) in [(r#""n":4096"#, r#""n":2097152"#), (r#""p":6"#, r#""p":1024"#)] {
+            assert!(keystore.contains(from));
+            let keystore = keystore.replace(from, to);
+            assert!(NadaValue::new_ecdsa_private_key_from_ethereum_keystore(&keystore, "password").is_err());
+        }
+        Ok(())
+    }
+}
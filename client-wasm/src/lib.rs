@@ -15,8 +15,23 @@
     clippy::todo
 )]
 
+mod cosmos;
+mod ecdsa;
+mod eddsa;
+mod eip712;
 mod errors;
+mod ethereum;
+mod hd;
+mod jose;
+mod key_formats;
+mod key_shares;
+mod keys;
+mod keystore;
 mod program;
+mod proto;
+mod rlp;
+mod schnorr;
+mod solana;
 mod values;
 
 pub use program::ProgramMetadata;
@@ -0,0 +1,412 @@
+//! Protobuf messages for the `nillion.values.v1.value` package.
+//!
+//! These mirror `nilvm/libs/node-api/proto/nillion/values/v1/value.proto` and must be kept in sync with it.
+
+use prost::{Message, Oneof};
+
+/// A named value.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct NamedValue {
+    /// The name of this value.
+    #[prost(string, tag = "1")]
+    pub(crate) name: String,
+
+    /// The value itself.
+    #[prost(message, optional, tag = "2")]
+    pub(crate) value: Option<Value>,
+}
+
+/// A value.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct Value {
+    /// The value itself.
+    #[prost(oneof = "value::Value", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18")]
+    pub(crate) value: Option<value::Value>,
+}
+
+/// Nested types for [Value].
+pub(crate) mod value {
+    use super::*;
+
+    /// The value itself.
+    #[derive(Clone, PartialEq, Oneof)]
+    pub(crate) enum Value {
+        /// A public boolean.
+        #[prost(message, tag = "1")]
+        PublicBoolean(PublicInteger),
+
+        /// A public integer.
+        #[prost(message, tag = "2")]
+        PublicInteger(PublicInteger),
+
+        /// A public unsigned integer.
+        #[prost(message, tag = "3")]
+        PublicUnsignedInteger(PublicInteger),
+
+        /// A shamir share of a secret boolean.
+        #[prost(message, tag = "4")]
+        ShamirShareBoolean(ShamirShare),
+
+        /// A shamir share of a secret integer.
+        #[prost(message, tag = "5")]
+        ShamirShareInteger(ShamirShare),
+
+        /// A shamir share of a secret unsigned integer.
+        #[prost(message, tag = "6")]
+        ShamirShareUnsignedInteger(ShamirShare),
+
+        /// An array.
+        #[prost(message, tag = "7")]
+        Array(Array),
+
+        /// A tuple.
+        #[prost(message, tag = "8")]
+        Tuple(Box<Tuple>),
+
+        /// The shamir shares of a secret blob.
+        #[prost(message, tag = "9")]
+        ShamirSharesBlob(ShamirSharesBlob),
+
+        /// An ecdsa private key share.
+        #[prost(message, tag = "10")]
+        EcdsaPrivateKeyShare(PrivateKeyShare),
+
+        /// An ecdsa signature share.
+        #[prost(message, tag = "11")]
+        EcdsaSignatureShare(EcdsaSignatureShare),
+
+        /// An ecdsa message digest.
+        #[prost(message, tag = "12")]
+        EcdsaMessageDigest(EcdsaMessageDigest),
+
+        /// An ecdsa public key.
+        #[prost(message, tag = "13")]
+        EcdsaPublicKey(PublicKey),
+
+        /// A store id.
+        #[prost(message, tag = "14")]
+        StoreId(StoreId),
+
+        /// An eddsa private key share.
+        #[prost(message, tag = "15")]
+        EddsaPrivateKeyShare(PrivateKeyShare),
+
+        /// An eddsa signature.
+        #[prost(message, tag = "16")]
+        EddsaSignature(EddsaSignature),
+
+        /// An eddsa message.
+        #[prost(message, tag = "17")]
+        EddsaMessage(EddsaMessage),
+
+        /// An eddsa public key.
+        #[prost(message, tag = "18")]
+        EddsaPublicKey(PublicKey),
+    }
+}
+
+/// A public integer, unsigned integer or boolean.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct PublicInteger {
+    /// The encoded value.
+    #[prost(bytes = "vec", tag = "1")]
+    pub(crate) value: Vec<u8>,
+}
+
+/// A shamir share.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ShamirShare {
+    /// The encoded share.
+    #[prost(bytes = "vec", tag = "1")]
+    pub(crate) value: Vec<u8>,
+}
+
+/// An array.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct Array {
+    /// The elements in the array.
+    #[prost(message, repeated, tag = "1")]
+    pub(crate) values: Vec<Value>,
+
+    /// The type of the elements in the array.
+    #[prost(message, optional, tag = "2")]
+    pub(crate) inner_type: Option<ValueType>,
+}
+
+/// A tuple.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct Tuple {
+    /// The left element.
+    #[prost(message, optional, boxed, tag = "1")]
+    pub(crate) left: Option<Box<Value>>,
+
+    /// The right element.
+    #[prost(message, optional, boxed, tag = "2")]
+    pub(crate) right: Option<Box<Value>>,
+}
+
+/// The shares of a secret blob.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ShamirSharesBlob {
+    /// The shares.
+    #[prost(message, repeated, tag = "1")]
+    pub(crate) shares: Vec<ShamirShare>,
+
+    /// The size of the blob before it was encoded.
+    #[prost(uint64, tag = "2")]
+    pub(crate) original_size: u64,
+}
+
+/// An ecdsa or eddsa private key share.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct PrivateKeyShare {
+    /// The index of this share.
+    #[prost(uint32, tag = "1")]
+    pub(crate) i: u32,
+
+    /// The secret share, little endian encoded.
+    #[prost(bytes = "vec", tag = "2")]
+    pub(crate) x: Vec<u8>,
+
+    /// The public key shared by all parties.
+    #[prost(bytes = "vec", tag = "3")]
+    pub(crate) shared_public_key: Vec<u8>,
+
+    /// The public shares of every party.
+    #[prost(bytes = "vec", repeated, tag = "4")]
+    pub(crate) public_shares: Vec<Vec<u8>>,
+}
+
+/// An ecdsa signature share.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct EcdsaSignatureShare {
+    /// The r component, little endian encoded.
+    #[prost(bytes = "vec", tag = "1")]
+    pub(crate) r: Vec<u8>,
+
+    /// The sigma component, little endian encoded.
+    #[prost(bytes = "vec", tag = "2")]
+    pub(crate) sigma: Vec<u8>,
+}
+
+/// An ecdsa message digest.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct EcdsaMessageDigest {
+    /// The 32 byte digest.
+    #[prost(bytes = "vec", tag = "1")]
+    pub(crate) digest: Vec<u8>,
+}
+
+/// An ecdsa or eddsa public key.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct PublicKey {
+    /// The encoded public key.
+    #[prost(bytes = "vec", tag = "1")]
+    pub(crate) public_key: Vec<u8>,
+}
+
+/// A store id.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct StoreId {
+    /// The 16 byte store id.
+    #[prost(bytes = "vec", tag = "1")]
+    pub(crate) store_id: Vec<u8>,
+}
+
+/// An eddsa signature.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct EddsaSignature {
+    /// The 64 byte signature.
+    #[prost(bytes = "vec", tag = "1")]
+    pub(crate) signature: Vec<u8>,
+}
+
+/// An eddsa message.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct EddsaMessage {
+    /// The message.
+    #[prost(bytes = "vec", tag = "1")]
+    pub(crate) message: Vec<u8>,
+}
+
+/// The type of a value.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ValueType {
+    /// The type itself.
+    #[prost(oneof = "value_type::ValueType", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17")]
+    pub(crate) value_type: Option<value_type::ValueType>,
+}
+
+/// Nested types for [ValueType].
+pub(crate) mod value_type {
+    use super::*;
+
+    /// The type itself.
+    #[derive(Clone, PartialEq, Oneof)]
+    pub(crate) enum ValueType {
+        /// A public integer.
+        #[prost(message, tag = "1")]
+        PublicInteger(()),
+
+        /// A public unsigned integer.
+        #[prost(message, tag = "2")]
+        PublicUnsignedInteger(()),
+
+        /// A public boolean.
+        #[prost(message, tag = "3")]
+        PublicBoolean(()),
+
+        /// A shamir share of a secret integer.
+        #[prost(message, tag = "4")]
+        ShamirShareInteger(()),
+
+        /// A shamir share of a secret unsigned integer.
+        #[prost(message, tag = "5")]
+        ShamirShareUnsignedInteger(()),
+
+        /// A shamir share of a secret boolean.
+        #[prost(message, tag = "6")]
+        ShamirShareBoolean(()),
+
+        /// An array.
+        #[prost(message, tag = "7")]
+        Array(Box<ArrayType>),
+
+        /// A tuple.
+        #[prost(message, tag = "8")]
+        Tuple(Box<TupleType>),
+
+        /// An ecdsa private key share.
+        #[prost(message, tag = "9")]
+        EcdsaPrivateKeyShare(()),
+
+        /// An ecdsa message digest.
+        #[prost(message, tag = "10")]
+        EcdsaMessageDigest(()),
+
+        /// An ecdsa signature share.
+        #[prost(message, tag = "11")]
+        EcdsaSignatureShare(()),
+
+        /// An ecdsa public key.
+        #[prost(message, tag = "12")]
+        EcdsaPublicKey(()),
+
+        /// A store id.
+        #[prost(message, tag = "13")]
+        StoreId(()),
+
+        /// An eddsa private key share.
+        #[prost(message, tag = "14")]
+        EddsaPrivateKeyShare(()),
+
+        /// An eddsa signature.
+        #[prost(message, tag = "15")]
+        EddsaSignature(()),
+
+        /// An eddsa message.
+        #[prost(message, tag = "16")]
+        EddsaMessage(()),
+
+        /// An eddsa public key.
+        #[prost(message, tag = "17")]
+        EddsaPublicKey(()),
+    }
+}
+
+/// The type of an array.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ArrayType {
+    /// The type of the elements in the array.
+    #[prost(message, optional, boxed, tag = "1")]
+    pub(crate) inner_type: Option<Box<ValueType>>,
+
+    /// The number of elements in the array.
+    #[prost(uint64, tag = "2")]
+    pub(crate) size: u64,
+}
+
+/// The type of a tuple.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct TupleType {
+    /// The type of the left element.
+    #[prost(message, optional, boxed, tag = "1")]
+    pub(crate) left: Option<Box<ValueType>>,
+
+    /// The type of the right element.
+    #[prost(message, optional, boxed, tag = "2")]
+    pub(crate) right: Option<Box<ValueType>>,
+}
+
+impl Value {
+    /// The type of this value, if it has one.
+    pub(crate) fn value_type(&self) -> Option<ValueType> {
+        use value::Value as V;
+        use value_type::ValueType as T;
+
+        let value_type = match self.value.as_ref()? {
+            V::PublicBoolean(_) => T::PublicBoolean(()),
+            V::PublicInteger(_) => T::PublicInteger(()),
+            V::PublicUnsignedInteger(_) => T::PublicUnsignedInteger(()),
+            V::ShamirShareBoolean(_) => T::ShamirShareBoolean(()),
+            V::ShamirShareInteger(_) => T::ShamirShareInteger(()),
+            V::ShamirShareUnsignedInteger(_) => T::ShamirShareUnsignedInteger(()),
+            V::Array(array) => T::Array(Box::new(ArrayType {
+                inner_type: array.inner_type.clone().map(Box::new),
+                size: array.values.len() as u64,
+            })),
+            V::Tuple(tuple) => T::Tuple(Box::new(TupleType {
+                left: tuple.left.as_ref().and_then(|left| left.value_type()).map(Box::new),
+                right: tuple.right.as_ref().and_then(|right| right.value_type()).map(Box::new),
+            })),
+            // Blobs can't be nested in other values.
+            V::ShamirSharesBlob(_) => return None,
+            V::EcdsaPrivateKeyShare(_) => T::EcdsaPrivateKeyShare(()),
+            V::EcdsaSignatureShare(_) => T::EcdsaSignatureShare(()),
+            V::EcdsaMessageDigest(_) => T::EcdsaMessageDigest(()),
+            V::EcdsaPublicKey(_) => T::EcdsaPublicKey(()),
+            V::StoreId(_) => T::StoreId(()),
+            V::EddsaPrivateKeyShare(_) => T::EddsaPrivateKeyShare(()),
+            V::EddsaSignature(_) => T::EddsaSignature(()),
+            V::EddsaMessage(_) => T::EddsaMessage(()),
+            V::EddsaPublicKey(_) => T::EddsaPublicKey(()),
+        };
+        Some(ValueType { value_type: Some(value_type) })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn array_type() {
+        let element = Value { value: Some(value::Value::ShamirShareInteger(ShamirShare { value: vec![1] })) };
+        let array = Value {
+            value: Some(value::Value::Array(Array {
+                values: vec![element.clone(), element.clone()],
+                inner_type: element.value_type(),
+            })),
+        };
+        let expected = ValueType {
+            value_type: Some(value_type::ValueType::Array(Box::new(ArrayType {
+                inner_type: Some(Box::new(ValueType {
+                    value_type: Some(value_type::ValueType::ShamirShareInteger(())),
+                })),
+                size: 2,
+            }))),
+        };
+        assert_eq!(array.value_type(), Some(expected));
+    }
+
+    #[wasm_bindgen_test]
+    fn named_value_encoding() {
+        let value = NamedValue {
+            name: "a".into(),
+            value: Some(Value { value: Some(value::Value::StoreId(StoreId { store_id: vec![1; 16] })) }),
+        };
+        let bytes = value.encode_to_vec();
+        assert_eq!(NamedValue::decode(bytes.as_slice()).ok(), Some(value));
+    }
+}
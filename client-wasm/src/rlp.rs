@@ -0,0 +1,94 @@
+//! Recursive length prefix encoding, as used by Ethereum transactions.
+
+/// The offset of the prefix of byte strings.
+const STRING_OFFSET: u8 = 0x80;
+
+/// The offset of the prefix of lists.
+const LIST_OFFSET: u8 = 0xc0;
+
+/// The longest payload whose length fits in the prefix byte.
+const MAX_SHORT_LENGTH: usize = 55;
+
+/// An item to be RLP encoded.
+pub(crate) enum Item {
+    /// A byte string.
+    Bytes(Vec<u8>),
+    /// A list of items.
+    List(Vec<Item>),
+}
+
+impl Item {
+    /// A big endian integer, without leading zeros.
+    pub(crate) fn integer(bytes: &[u8]) -> Self {
+        let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(bytes.len());
+        Item::Bytes(bytes.get(start..).unwrap_or_default().to_vec())
+    }
+
+    /// Encode this item.
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        match self {
+            Item::Bytes(bytes) => match bytes.as_slice() {
+                [byte] if *byte < STRING_OFFSET => vec![*byte],
+                _ => {
+                    let mut encoded = encode_length(bytes.len(), STRING_OFFSET);
+                    encoded.extend_from_slice(bytes);
+                    encoded
+                }
+            },
+            Item::List(items) => {
+                let payload: Vec<u8> = items.iter().flat_map(Item::encode).collect();
+                let mut encoded = encode_length(payload.len(), LIST_OFFSET);
+                encoded.extend(payload);
+                encoded
+            }
+        }
+    }
+}
+
+/// Encode the prefix for a payload of some length.
+fn encode_length(length: usize, offset: u8) -> Vec<u8> {
+    match u8::try_from(length) {
+        Ok(length) if usize::from(length) <= MAX_SHORT_LENGTH => vec![offset.saturating_add(length)],
+        _ => {
+            let length = length.to_be_bytes();
+            let start = length.iter().position(|byte| *byte != 0).unwrap_or(length.len());
+            let length = length.get(start..).unwrap_or_default();
+            // The length of the length is at most 8 so this can't overflow a byte.
+            let length_of_length = u8::try_from(length.len()).unwrap_or(u8::MAX);
+            let mut encoded = vec![offset.saturating_add(55).saturating_add(length_of_length)];
+            encoded.extend_from_slice(length);
+            encoded
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn bytes(value: &str) -> Item {
+        Item::Bytes(value.as_bytes().to_vec())
+    }
+
+    #[wasm_bindgen_test]
+    fn encode() {
+        // Examples from the Ethereum yellow paper and wiki.
+        assert_eq!(bytes("dog").encode(), b"\x83dog");
+        assert_eq!(Item::List(vec![bytes("cat"), bytes("dog")]).encode(), b"\xc8\x83cat\x83dog");
+        assert_eq!(bytes("").encode(), [0x80]);
+        assert_eq!(Item::List(vec![]).encode(), [0xc0]);
+        assert_eq!(Item::integer(&[0, 0]).encode(), [0x80]);
+        assert_eq!(Item::integer(&[0x0f]).encode(), [0x0f]);
+        assert_eq!(Item::integer(&[0x04, 0x00]).encode(), [0x82, 0x04, 0x00]);
+        let empty = || Item::List(vec![]);
+        let set =
+            Item::List(vec![empty(), Item::List(vec![empty()]), Item::List(vec![empty(), Item::List(vec![empty()])])]);
+        assert_eq!(set.encode(), [0xc7, 0xc0, 0xc1, 0xc0, 0xc3, 0xc0, 0xc1, 0xc0]);
+
+        let lorem = "Lorem ipsum dolor sit amet, consectetur adipisicing elit";
+        let mut expected = vec![0xb8, 0x38];
+        expected.extend_from_slice(lorem.as_bytes());
+        assert_eq!(bytes(lorem).encode(), expected);
+    }
+}
@@ -0,0 +1,240 @@
+//! BIP-340 Schnorr signature utilities.
+//!
+//! Schnorr signatures aren't values the network can store or produce, so they are represented by their own type
+//! rather than as a `NadaValue`. Secp256k1 public keys are shared with ecdsa and use `EcdsaPublicKey` values.
+use crate::{
+    ecdsa::{parse_public_key, public_key_from_value},
+    errors::{JsResult, ValueError},
+    values::{to_byte_array, NadaValue},
+};
+use js_sys::Uint8Array;
+use k256::schnorr::{Signature, VerifyingKey};
+use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// A BIP-340 Schnorr signature.
+#[wasm_bindgen(inspectable)]
+#[derive(Clone)]
+pub struct SchnorrSignature {
+    /// The x coordinate of the nonce point, in binary format
+    r: Vec<u8>,
+    /// s component of the signature in binary format
+    s: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl SchnorrSignature {
+    /// Construct a new instance the components.
+    #[wasm_bindgen(constructor)]
+    pub fn new(r: Vec<u8>, s: Vec<u8>) -> Self {
+        Self { r, s }
+    }
+
+    /// Construct a new instance from the 64 byte `r || s` encoding.
+    ///
+    /// @param {Uint8Array} signature - The 64 byte signature
+    /// @return {SchnorrSignature} The signature
+    /// @throws {Error} if the signature is not 64 bytes long.
+    ///
+    /// @example
+    /// const signature = SchnorrSignature.from_bytes(bytes);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn from_bytes(signature: &[u8]) -> JsResult<SchnorrSignature> {
+        if signature.len() != 64 {
+            return Err(ValueError::new_err("Schnorr signature must be 64 bytes long"));
+        }
+        let (r, s) = signature.split_at(32);
+        Ok(Self::new(r.to_vec(), s.to_vec()))
+    }
+
+    /// Access r component of the signature
+    pub fn r(&self) -> Uint8Array {
+        to_byte_array(&self.r)
+    }
+
+    /// Access s component of the signature
+    pub fn s(&self) -> Uint8Array {
+        to_byte_array(&self.s)
+    }
+
+    /// Access value of the signature
+    pub fn signature(&self) -> Uint8Array {
+        let mut signature = self.r.clone();
+        signature.extend_from_slice(&self.s);
+        to_byte_array(&signature)
+    }
+
+    /// Verify this signature against a public key and a message.
+    ///
+    /// @param {NadaValue} public_key - The `EcdsaPublicKey` value the signature should be valid for, only its x
+    ///   coordinate is used
+    /// @param {Uint8Array} message - The message that was signed, e.g. a Taproot signature hash
+    /// @return {boolean} Whether the signature is valid
+    /// @throws {Error} if the value provided is not an ecdsa public key.
+    ///
+    /// @example
+    /// const valid = signature.verify(publicKey, sighash);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn verify(&self, public_key: &NadaValue, message: &[u8]) -> JsResult<bool> {
+        let public_key = public_key.to_x_only_public_key()?;
+        self.verify_bytes(&public_key, message)
+    }
+
+    /// Verify this signature against an x-only public key and a message.
+    ///
+    /// @param {Uint8Array} public_key - The 32 byte x-only public key
+    /// @param {Uint8Array} message - The message that was signed, e.g. a Taproot signature hash
+    /// @return {boolean} Whether the signature is valid, which it isn't if the key is not a point on the curve
+    /// @throws {Error} if the public key is not 32 bytes long.
+    ///
+    /// @example
+    /// const valid = signature.verify_bytes(publicKey, sighash);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn verify_bytes(&self, public_key: &[u8], message: &[u8]) -> JsResult<bool> {
+        if public_key.len() != 32 {
+            return Err(ValueError::new_err("Schnorr public key must be 32 bytes long"));
+        }
+        let Ok(public_key) = VerifyingKey::from_bytes(public_key) else {
+            return Ok(false);
+        };
+        let signature = [self.r.as_slice(), self.s.as_slice()].concat();
+        let Ok(signature) = Signature::try_from(signature.as_slice()) else {
+            return Ok(false);
+        };
+        // BIP-340 signs the message itself rather than a hash of it.
+        Ok(public_key.verify_raw(message, &signature).is_ok())
+    }
+
+    /// Compute the BIP-340 tagged hash `sha256(sha256(tag) || sha256(tag) || message)`.
+    ///
+    /// @param {string} tag - The tag, e.g. `TapSighash`
+    /// @param {Uint8Array} message - The message to hash
+    /// @return {Uint8Array} The 32 byte hash
+    ///
+    /// @example
+    /// const hash = SchnorrSignature.tagged_hash("TapSighash", data);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn tagged_hash(tag: &str, message: &[u8]) -> Vec<u8> {
+        tagged_hash(tag, &[message]).to_vec()
+    }
+}
+
+#[wasm_bindgen]
+impl NadaValue {
+    /// Get the 32 byte x-only encoding of an `EcdsaPublicKey` value, as used by BIP-340 and Taproot.
+    ///
+    /// @return {Uint8Array} The x coordinate of the public key
+    /// @throws {Error} if the value is not a valid ecdsa public key.
+    ///
+    /// @example
+    /// const xOnly = publicKey.to_x_only_public_key();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_x_only_public_key(&self) -> JsResult<Vec<u8>> {
+        let public_key = parse_public_key(&public_key_from_value(self)?)?;
+        let encoded = public_key.to_bytes(true);
+        Ok(encoded.get(1..).unwrap_or_default().to_vec())
+    }
+}
+
+/// Compute a BIP-340 tagged hash over the concatenation of some data.
+fn tagged_hash(tag: &str, data: &[&[u8]]) -> [u8; 32] {
+    let tag = Sha256::digest(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.update(tag);
+    hasher.update(tag);
+    for part in data {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_test::*;
+
+    fn decode(value: &str) -> Vec<u8> {
+        hex::decode(value).unwrap_or_default()
+    }
+
+    // Test vectors from BIP-340.
+    const VECTORS: [(&str, &str, &str, bool); 7] = [
+        (
+            "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA821525F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0",
+            true,
+        ),
+        (
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE33418906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A",
+            true,
+        ),
+        (
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "FFF97BD5755EEEA420453A14355235D382F6472F8568A18B2F057A14602975563CC27944640AC607CD107AE10923D9EF7A73C643E166BE5EBEAFA34B1AC553E2",
+            false,
+        ),
+        (
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "1FA62E331EDBC21C394792D2AB1100A7B432B013DF3F6FF4F99FCB33E0E1515F28890B3EDB6E7189B630448B515CE4F8622A954CFE545735AAEA5134FCCDB2BD",
+            false,
+        ),
+        (
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769961764B3AA9B2FFCB6EF947B6887A226E8D7C93E00C5ED0C1834FF0D0C2E6DA6",
+            false,
+        ),
+        (
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE3341FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141",
+            false,
+        ),
+        // The public key exceeds the field size.
+        (
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+            false,
+        ),
+    ];
+
+    #[wasm_bindgen_test]
+    fn verify_vectors() -> Result<(), JsValue> {
+        for (index, (public_key, message, signature, valid)) in VECTORS.into_iter().enumerate() {
+            let signature = SchnorrSignature::from_bytes(&decode(signature))?;
+            assert_eq!(signature.verify_bytes(&decode(public_key), &decode(message))?, valid, "vector {index}");
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn x_only_public_key() -> Result<(), JsValue> {
+        let Some((public_key, message, signature, _)) = VECTORS.into_iter().nth(1) else {
+            return Err("missing test vector".into());
+        };
+        let mut compressed = vec![0x03];
+        compressed.extend_from_slice(&decode(public_key));
+        let value = NadaValue::new_ecdsa_public_key(compressed)?;
+        assert_eq!(value.to_x_only_public_key()?, decode(public_key));
+
+        let signature = SchnorrSignature::from_bytes(&decode(signature))?;
+        assert!(signature.verify(&value, &decode(message))?);
+        assert!(SchnorrSignature::from_bytes(&[0; 63]).is_err());
+        assert!(signature.verify_bytes(&[0; 33], &[]).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn tagged_hashes() {
+        let tag = Sha256::digest(b"TapSighash");
+        let expected = Sha256::digest([tag.as_slice(), tag.as_slice(), &[1, 2, 3]].concat());
+        assert_eq!(SchnorrSignature::tagged_hash("TapSighash", &[1, 2, 3]), expected.to_vec());
+    }
+}
@@ -0,0 +1,429 @@
+//! Solana transaction helpers.
+use crate::{
+    eddsa::{public_key_from_value, verify_strict},
+    errors::{JsResult, ValueError},
+    values::{EddsaSignature, NadaValue},
+};
+use serde::Deserialize;
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+
+/// The bit set in the first byte of versioned messages.
+const VERSION_PREFIX: u8 = 0x80;
+
+#[wasm_bindgen]
+impl NadaValue {
+    /// Encode an `EddsaPublicKey` value as a base58 Solana address.
+    ///
+    /// @return {string} The address
+    /// @throws {Error} if the value is not an eddsa public key.
+    ///
+    /// @example
+    /// const address = publicKey.to_solana_address();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_solana_address(&self) -> JsResult<String> {
+        Ok(bs58::encode(public_key_from_value(self)?).into_string())
+    }
+}
+
+/// A Solana transaction, made of a message and the signatures of its signers.
+#[wasm_bindgen]
+pub struct SolanaTransaction {
+    message: Vec<u8>,
+    signers: Vec<[u8; 32]>,
+    signatures: Vec<Option<[u8; 64]>>,
+}
+
+#[wasm_bindgen]
+impl SolanaTransaction {
+    /// Compile a new legacy transaction out of its fee payer, recent blockhash and instructions.
+    ///
+    /// Accounts are deduplicated and ordered the same way `Message.compile` in `@solana/web3.js` does: the fee payer
+    /// first, then signers before non signers and writable accounts before read-only ones, in order of appearance.
+    ///
+    /// @param {object} fields - The `feePayer` and `recentBlockhash` as base58 strings, and the `instructions`, each
+    ///   one with a `programId`, its `accounts` as `{ pubkey, isSigner, isWritable }` objects and its `data` either as
+    ///   a base58 string or as an array of bytes
+    /// @return {SolanaTransaction} The unsigned transaction
+    /// @throws {Error} if a field is missing or malformed.
+    ///
+    /// @example
+    /// const tx = new SolanaTransaction({ feePayer, recentBlockhash, instructions: [{ programId, accounts, data }] });
+    #[wasm_bindgen(constructor, skip_jsdoc)]
+    pub fn new(fields: JsValue) -> JsResult<SolanaTransaction> {
+        let fields: TransactionFields = serde_wasm_bindgen::from_value(fields)
+            .map_err(|e| ValueError::new_err(&format!("Invalid transaction: {e}")))?;
+        let message =
+            compile_message(&fields).map_err(|e| ValueError::new_err(&format!("Invalid transaction: {e}")))?;
+        Self::from_message(message)
+    }
+
+    /// Create a transaction out of an already serialized legacy or versioned message.
+    ///
+    /// @param {Uint8Array} message - The serialized message, e.g. the output of `message.serialize()` in
+    ///   `@solana/web3.js`
+    /// @return {SolanaTransaction} The unsigned transaction
+    /// @throws {Error} if the message is malformed.
+    ///
+    /// @example
+    /// const tx = SolanaTransaction.from_message(message.serialize());
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn from_message(message: Vec<u8>) -> JsResult<SolanaTransaction> {
+        let signers = parse_signers(&message).map_err(|e| ValueError::new_err(&format!("Invalid message: {e}")))?;
+        Ok(Self { signatures: vec![None; signers.len()], message, signers })
+    }
+
+    /// The serialized message.
+    pub fn message(&self) -> Vec<u8> {
+        self.message.clone()
+    }
+
+    /// The serialized message as an `EddsaMessage` value, ready to be signed.
+    ///
+    /// @return {NadaValue} The message to sign
+    ///
+    /// @example
+    /// const message = tx.to_eddsa_message();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_eddsa_message(&self) -> JsResult<NadaValue> {
+        NadaValue::new_eddsa_message(self.message.clone())
+    }
+
+    /// Add the signature of one of the signers of this transaction.
+    ///
+    /// @param {EddsaSignature} signature - The signature over this transaction's message
+    /// @param {NadaValue} public_key - The `EddsaPublicKey` value of the signer
+    /// @throws {Error} if the public key is not a signer of this transaction or the signature is not valid.
+    ///
+    /// @example
+    /// tx.add_signature(signature, publicKey);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn add_signature(&mut self, signature: &EddsaSignature, public_key: &NadaValue) -> JsResult<()> {
+        let public_key = public_key_from_value(public_key)?;
+        let signature = signature.to_bytes()?;
+        let index = self
+            .signers
+            .iter()
+            .position(|signer| *signer == public_key)
+            .ok_or_else(|| ValueError::new_err("public key is not a signer of this transaction"))?;
+        if !verify_strict(&public_key, &self.message, &signature) {
+            return Err(ValueError::new_err("signature is not valid for this transaction"));
+        }
+        if let Some(slot) = self.signatures.get_mut(index) {
+            *slot = Some(signature);
+        }
+        Ok(())
+    }
+
+    /// Serialize this transaction in the wire format expected by `sendTransaction`.
+    ///
+    /// @return {Uint8Array} The signed transaction
+    /// @throws {Error} if a signer's signature is missing.
+    ///
+    /// @example
+    /// const raw = tx.to_signed_bytes();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_signed_bytes(&self) -> JsResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+        encode_length(&mut bytes, self.signatures.len()).map_err(|e| ValueError::new_err(&e))?;
+        for (signer, signature) in self.signers.iter().zip(&self.signatures) {
+            let signature = signature.ok_or_else(|| {
+                let signer = bs58::encode(signer).into_string();
+                ValueError::new_err(&format!("missing signature for {signer}"))
+            })?;
+            bytes.extend_from_slice(&signature);
+        }
+        bytes.extend_from_slice(&self.message);
+        Ok(bytes)
+    }
+}
+
+/// The transaction fields as provided from javascript.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionFields {
+    fee_payer: String,
+    recent_blockhash: String,
+    instructions: Vec<InstructionFields>,
+}
+
+/// An instruction to be compiled into a message.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstructionFields {
+    program_id: String,
+    #[serde(default, alias = "keys")]
+    accounts: Vec<AccountFields>,
+    #[serde(default)]
+    data: Option<InstructionData>,
+}
+
+/// An account used by an instruction.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountFields {
+    pubkey: String,
+    #[serde(default)]
+    is_signer: bool,
+    #[serde(default)]
+    is_writable: bool,
+}
+
+/// The data of an instruction.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum InstructionData {
+    Base58(String),
+    Bytes(Vec<u8>),
+}
+
+/// An account in a compiled message.
+struct AccountMeta {
+    pubkey: [u8; 32],
+    is_signer: bool,
+    is_writable: bool,
+}
+
+/// Compile a legacy message.
+fn compile_message(fields: &TransactionFields) -> Result<Vec<u8>, String> {
+    let fee_payer = decode_key(&fields.fee_payer, "feePayer")?;
+    let mut accounts = vec![AccountMeta { pubkey: fee_payer, is_signer: true, is_writable: true }];
+    let mut add_account = |pubkey: [u8; 32], is_signer: bool, is_writable: bool| match accounts
+        .iter_mut()
+        .find(|account| account.pubkey == pubkey)
+    {
+        Some(account) => {
+            account.is_signer |= is_signer;
+            account.is_writable |= is_writable;
+        }
+        None => accounts.push(AccountMeta { pubkey, is_signer, is_writable }),
+    };
+    let mut instructions = Vec::new();
+    for instruction in &fields.instructions {
+        let program_id = decode_key(&instruction.program_id, "programId")?;
+        add_account(program_id, false, false);
+        let mut keys = Vec::new();
+        for account in &instruction.accounts {
+            let pubkey = decode_key(&account.pubkey, "account")?;
+            add_account(pubkey, account.is_signer, account.is_writable);
+            keys.push(pubkey);
+        }
+        let data = match &instruction.data {
+            Some(InstructionData::Base58(data)) => {
+                bs58::decode(data).into_vec().map_err(|e| format!("instruction data is not valid base58: {e}"))?
+            }
+            Some(InstructionData::Bytes(data)) => data.clone(),
+            None => Vec::new(),
+        };
+        instructions.push((program_id, keys, data));
+    }
+    // Sorting is stable so the fee payer stays first and the remaining accounts keep their insertion order.
+    accounts.sort_by_key(|account| (!account.is_signer, !account.is_writable));
+
+    let count = |signer: bool, writable: bool| {
+        let count = accounts.iter().filter(|account| account.is_signer == signer && account.is_writable == writable);
+        u8::try_from(count.count()).map_err(|_| "too many accounts".to_string())
+    };
+    let signers = accounts.iter().filter(|account| account.is_signer).count();
+    let mut message = vec![
+        u8::try_from(signers).map_err(|_| "too many signers".to_string())?,
+        count(true, false)?,
+        count(false, false)?,
+    ];
+    encode_length(&mut message, accounts.len())?;
+    for account in &accounts {
+        message.extend_from_slice(&account.pubkey);
+    }
+    message.extend_from_slice(&decode_key(&fields.recent_blockhash, "recentBlockhash")?);
+    let index = |pubkey: &[u8; 32]| {
+        let index = accounts.iter().position(|account| account.pubkey == *pubkey).unwrap_or_default();
+        u8::try_from(index).map_err(|_| "too many accounts".to_string())
+    };
+    encode_length(&mut message, instructions.len())?;
+    for (program_id, keys, data) in &instructions {
+        message.push(index(program_id)?);
+        encode_length(&mut message, keys.len())?;
+        for key in keys {
+            message.push(index(key)?);
+        }
+        encode_length(&mut message, data.len())?;
+        message.extend_from_slice(data);
+    }
+    Ok(message)
+}
+
+/// Get the public keys of the accounts that need to sign a serialized message.
+fn parse_signers(message: &[u8]) -> Result<Vec<[u8; 32]>, String> {
+    let message = match message.first() {
+        Some(prefix) if prefix & VERSION_PREFIX != 0 => {
+            if *prefix != VERSION_PREFIX {
+                return Err(format!("unsupported message version {}", prefix & !VERSION_PREFIX));
+            }
+            message.get(1..).unwrap_or_default()
+        }
+        _ => message,
+    };
+    let (&signers, rest) = message.split_first().ok_or_else(|| "message is empty".to_string())?;
+    let rest = rest.get(2..).ok_or_else(|| "message header is truncated".to_string())?;
+    let (account_count, mut rest) = decode_length(rest)?;
+    if usize::from(signers) > account_count {
+        return Err("message has more signers than accounts".to_string());
+    }
+    let mut keys = Vec::new();
+    for _ in 0..account_count {
+        let (key, remaining) =
+            rest.split_first_chunk::<32>().ok_or_else(|| "account keys are truncated".to_string())?;
+        keys.push(*key);
+        rest = remaining;
+    }
+    keys.truncate(usize::from(signers));
+    Ok(keys)
+}
+
+/// Append a length encoded as a compact-u16.
+fn encode_length(output: &mut Vec<u8>, length: usize) -> Result<(), String> {
+    let mut remaining = u16::try_from(length).map_err(|_| format!("length {length} is too large"))?;
+    loop {
+        let byte = u8::try_from(remaining & 0x7f).unwrap_or_default();
+        remaining = remaining.wrapping_shr(7);
+        if remaining == 0 {
+            output.push(byte);
+            return Ok(());
+        }
+        output.push(byte | 0x80);
+    }
+}
+
+/// Read a length encoded as a compact-u16, returning it along with the remaining bytes.
+fn decode_length(bytes: &[u8]) -> Result<(usize, &[u8]), String> {
+    let mut length = 0usize;
+    for (index, byte) in bytes.iter().enumerate().take(3) {
+        length |= usize::from(byte & 0x7f).wrapping_shl(u32::try_from(index).unwrap_or_default().wrapping_mul(7));
+        if byte & 0x80 == 0 {
+            let rest = bytes.get(index.saturating_add(1)..).unwrap_or_default();
+            return Ok((length, rest));
+        }
+    }
+    Err("invalid compact length".to_string())
+}
+
+/// Decode a base58 public key or blockhash.
+fn decode_key(value: &str, field: &str) -> Result<[u8; 32], String> {
+    let mut key = [0; 32];
+    let length = bs58::decode(value).onto(&mut key).map_err(|e| format!("{field} is not valid base58: {e}"))?;
+    if length != 32 {
+        return Err(format!("{field} must be 32 bytes long"));
+    }
+    Ok(key)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use wasm_bindgen_test::*;
+
+    const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+
+    fn encode(bytes: &[u8]) -> String {
+        bs58::encode(bytes).into_string()
+    }
+
+    fn transfer(fee_payer: &[u8; 32]) -> Result<JsValue, JsValue> {
+        let fee_payer = encode(fee_payer);
+        let fields = format!(
+            r#"{{
+                "feePayer": "{fee_payer}",
+                "recentBlockhash": "{}",
+                "instructions": [{{
+                    "programId": "{SYSTEM_PROGRAM}",
+                    "accounts": [
+                        {{ "pubkey": "{fee_payer}", "isSigner": true, "isWritable": true }},
+                        {{ "pubkey": "{}", "isSigner": false, "isWritable": true }}
+                    ],
+                    "data": [2, 0, 0, 0, 232, 3, 0, 0, 0, 0, 0, 0]
+                }}]
+            }}"#,
+            encode(&[9; 32]),
+            encode(&[2; 32]),
+        );
+        js_sys::JSON::parse(&fields)
+    }
+
+    #[wasm_bindgen_test]
+    fn solana_address() -> Result<(), JsValue> {
+        // RFC 8032 section 7.1, test 1.
+        let public_key = hex::decode("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a")
+            .map_err(|e| e.to_string())?;
+        let value = NadaValue::new_eddsa_public_key(public_key)?;
+        assert_eq!(value.to_solana_address()?, "FVen3X669xLzsi6N2V91DoiyzHzg1uAgqiT8jZ9nS96Z");
+        assert_eq!(NadaValue::new_eddsa_public_key(vec![0; 32])?.to_solana_address()?, SYSTEM_PROGRAM);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn transfer_transaction() -> Result<(), JsValue> {
+        let signing_key = SigningKey::from_bytes(&[1; 32]);
+        let public_key = signing_key.verifying_key().to_bytes();
+        let mut tx = SolanaTransaction::new(transfer(&public_key)?)?;
+
+        let mut expected = vec![1, 0, 1, 3];
+        expected.extend_from_slice(&public_key);
+        expected.extend_from_slice(&[2; 32]);
+        expected.extend_from_slice(&[0; 32]);
+        expected.extend_from_slice(&[9; 32]);
+        expected.extend_from_slice(&[1, 2, 2, 0, 1, 12, 2, 0, 0, 0, 232, 3, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(tx.message(), expected);
+        assert!(tx.to_signed_bytes().is_err());
+
+        let signature = signing_key.sign(&expected).to_bytes();
+        let (r, z) = signature.split_at(32);
+        let signature = EddsaSignature::new(r.to_vec(), z.to_vec());
+        let other = NadaValue::new_eddsa_public_key(vec![2; 32])?;
+        assert!(tx.add_signature(&signature, &other).is_err());
+
+        let public_key = NadaValue::new_eddsa_public_key(public_key.to_vec())?;
+        let tampered = EddsaSignature::new(z.to_vec(), r.to_vec());
+        assert!(tx.add_signature(&tampered, &public_key).is_err());
+        tx.add_signature(&signature, &public_key)?;
+
+        let mut signed = vec![1];
+        signed.extend_from_slice(r);
+        signed.extend_from_slice(z);
+        signed.extend_from_slice(&expected);
+        assert_eq!(tx.to_signed_bytes()?, signed);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn versioned_message() -> Result<(), JsValue> {
+        let public_key = [7; 32];
+        let legacy = SolanaTransaction::new(transfer(&public_key)?)?.message();
+        let mut message = vec![VERSION_PREFIX];
+        message.extend_from_slice(&legacy);
+        // No address lookup tables.
+        message.push(0);
+        let tx = SolanaTransaction::from_message(message.clone())?;
+        assert_eq!(tx.signers, vec![public_key]);
+
+        if let Some(prefix) = message.first_mut() {
+            *prefix = VERSION_PREFIX | 1;
+        }
+        assert!(SolanaTransaction::from_message(message).is_err());
+        assert!(SolanaTransaction::from_message(vec![1, 0, 0, 1]).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn compact_lengths() -> Result<(), JsValue> {
+        for (length, encoded) in
+            [(0, vec![0]), (0x7f, vec![0x7f]), (0x80, vec![0x80, 1]), (0xffff, vec![0xff, 0xff, 3])]
+        {
+            let mut output = Vec::new();
+            encode_length(&mut output, length)?;
+            assert_eq!(output, encoded);
+            assert_eq!(decode_length(&encoded)?, (length, &[][..]));
+        }
+        assert!(encode_length(&mut Vec::new(), 0x10000).is_err());
+        Ok(())
+    }
+}
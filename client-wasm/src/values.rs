@@ -1,17 +1,31 @@
 //! NadaValues.
-use crate::errors::{JsResult, ValueError};
+use crate::{
+    eip712,
+    errors::{JsResult, ValueError},
+    proto,
+};
 use js_sys::{Array, Object, Uint8Array};
 use nillion_client_core::{
     generic_ec::{curves::Secp256k1, serde::CurveName, Curve, NonZero, Point, Scalar, SecretScalar},
-    key_share::{DirtyCoreKeyShare, DirtyKeyInfo, Validate},
+    key_share::{DirtyCoreKeyShare, DirtyKeyInfo, Validate, VssSetup},
     privatekey::{ThresholdPrivateKey, ThresholdPrivateKeyShare},
     publickey::EcdsaPublicKeyArray,
     signature,
     signature::EcdsaSignatureShare,
     values::{BigInt, BigUint, BlobPrimitiveType, Clear, Encoded, EncodedModularNumber, Encrypted, PartyJar},
 };
+use prost::Message;
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
 use std::{collections::HashMap, str::FromStr};
-use wasm_bindgen::{prelude::wasm_bindgen, JsError, JsValue};
+use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsError, JsValue};
+use zeroize::{Zeroize, Zeroizing};
+
+/// The maximum number of parties `SecretMasker.unmask_robust` accepts.
+///
+/// Robust unmasking decodes every subset of `polynomial_degree + 1` shares, which grows exponentially with the number
+/// of parties. With 16 parties that's at most 12870 subsets.
+const MAX_ROBUST_UNMASK_PARTIES: usize = 16;
 
 /// NadaValue
 ///
@@ -19,8 +33,11 @@ use wasm_bindgen::{prelude::wasm_bindgen, JsError, JsValue};
 /// to encode numerical and binary values. It also provides methods to decode
 /// the value into a numerical form.
 ///
+/// Its `toJSON`, `toString` and node's `util.inspect` representations are redacted so values can be logged safely, use
+/// `reveal` to access the contents of a value.
+///
 /// @hideconstructor
-#[wasm_bindgen(inspectable)]
+#[wasm_bindgen]
 #[cfg_attr(test, derive(Debug, Clone, PartialEq))]
 pub struct NadaValue(pub(crate) nillion_client_core::values::NadaValue<Clear>);
 
@@ -123,6 +140,46 @@ impl NadaValue {
         Ok(Self(value))
     }
 
+    /// Create a new secret rational value.
+    ///
+    /// The value is encoded as a secret integer scaled by `10^scale`. The scale is not stored in the value so it
+    /// must be provided again when decoding it via `to_rational`.
+    ///
+    /// @param {string} value - The value must be a valid decimal representation with at most `scale` fractional digits.
+    /// @param {number} scale - The number of fractional digits.
+    /// @param {EncodedModulo} modulo - The modulo of the masker this value will be masked with.
+    /// @return {NadaValue} The encoded secret corresponding to the value provided
+    /// @throws {Error} if the scaled value doesn't fit in the masker's prime.
+    ///
+    /// @example
+    /// const value = NadaValue.new_secret_rational("-12.345", 3, masker.modulo());
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn new_secret_rational(value: &str, scale: u32, modulo: &EncodedModulo) -> JsResult<NadaValue> {
+        let value = parse_rational(value, scale, modulo)?;
+        let value = nillion_client_core::values::NadaValue::new_secret_integer(value);
+        Ok(Self(value))
+    }
+
+    /// Create a new public rational value.
+    ///
+    /// The value is encoded as a public integer scaled by `10^scale`. The scale is not stored in the value so it
+    /// must be provided again when decoding it via `to_rational`.
+    ///
+    /// @param {string} value - The value must be a valid decimal representation with at most `scale` fractional digits.
+    /// @param {number} scale - The number of fractional digits.
+    /// @param {EncodedModulo} modulo - The modulo of the masker this value will be used with.
+    /// @return {NadaValue} The encoded public variable corresponding to the value provided
+    /// @throws {Error} if the scaled value doesn't fit in the masker's prime.
+    ///
+    /// @example
+    /// const value = NadaValue.new_public_rational("0.5", 2, masker.modulo());
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn new_public_rational(value: &str, scale: u32, modulo: &EncodedModulo) -> JsResult<NadaValue> {
+        let value = parse_rational(value, scale, modulo)?;
+        let value = nillion_client_core::values::NadaValue::new_integer(value);
+        Ok(Self(value))
+    }
+
     /// Create a new ecdsa private key
     ///
     /// @param {Uint8Array} value - The ecdsa private key in binary (byte array) encoded format
@@ -153,6 +210,62 @@ impl NadaValue {
         Ok(Self(secret))
     }
 
+    /// Create a new ecdsa digest message by hashing a message using SHA-256.
+    ///
+    /// @param {Uint8Array} message - The message to be hashed
+    /// @return {NadaValue} The ecdsa digest message for the message provided
+    ///
+    /// @example
+    /// const value = NadaValue.new_sha256_digest_message(new TextEncoder().encode("hello"));
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn new_sha256_digest_message(message: &[u8]) -> NadaValue {
+        Self::digest_message(Sha256::digest(message).into())
+    }
+
+    /// Create a new ecdsa digest message by hashing a message using Keccak-256.
+    ///
+    /// @param {Uint8Array} message - The message to be hashed
+    /// @return {NadaValue} The ecdsa digest message for the message provided
+    ///
+    /// @example
+    /// const value = NadaValue.new_keccak256_digest_message(new TextEncoder().encode("hello"));
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn new_keccak256_digest_message(message: &[u8]) -> NadaValue {
+        Self::digest_message(Keccak256::digest(message).into())
+    }
+
+    /// Create a new ecdsa digest message for an Ethereum personal message, as described in EIP-191.
+    ///
+    /// The message is prefixed with `"\x19Ethereum Signed Message:\n"` and its length before being hashed using
+    /// Keccak-256, which is what `personal_sign` does.
+    ///
+    /// @param {Uint8Array} message - The message to be signed
+    /// @return {NadaValue} The ecdsa digest message for the message provided
+    ///
+    /// @example
+    /// const value = NadaValue.new_eip191_digest_message(new TextEncoder().encode("hello"));
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn new_eip191_digest_message(message: &[u8]) -> NadaValue {
+        let mut hasher = Keccak256::new();
+        hasher.update(format!("\x19Ethereum Signed Message:\n{}", message.len()));
+        hasher.update(message);
+        Self::digest_message(hasher.finalize().into())
+    }
+
+    /// Create a new ecdsa digest message for typed structured data, as described in EIP-712.
+    ///
+    /// @param {string} typed_data - The typed data as JSON, in the format accepted by `eth_signTypedData_v4`: an
+    ///   object with the `types`, `primaryType`, `domain` and `message` keys
+    /// @return {NadaValue} The ecdsa digest message for the typed data provided
+    /// @throws {Error} if the typed data is malformed or the message doesn't match its types.
+    ///
+    /// @example
+    /// const value = NadaValue.new_eip712_digest_message(JSON.stringify({ types, primaryType: "Mail", domain, message }));
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn new_eip712_digest_message(typed_data: &str) -> JsResult<NadaValue> {
+        Ok(Self::digest_message(eip712::hash_typed_data(typed_data)?))
+    }
+
     /// Create a new ecdsa signature.
     ///
     /// @param {Uint8Array} r - The r component of the signature in binary (byte array) encoded format
@@ -252,6 +365,83 @@ impl NadaValue {
         Ok(Self(nillion_client_core::values::NadaValue::new_store_id(array)))
     }
 
+    /// Create a new array value.
+    ///
+    /// All the elements must have the same type.
+    ///
+    /// @param {NadaValue[]} values - The elements of the array, it can't be empty.
+    /// @return {NadaValue} The array containing the values provided
+    /// @throws {Error} if the array is empty, its elements have different types or any of them is a secret blob.
+    ///
+    /// @example
+    /// const value = NadaValue.new_array([NadaValue.new_secret_integer("1"), NadaValue.new_secret_integer("2")]);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn new_array(values: Vec<NadaValue>) -> JsResult<NadaValue> {
+        reject_blobs(values.iter().map(|value| &value.0))?;
+        let values = values.into_iter().map(NadaValue::into_inner).collect();
+        let value = nillion_client_core::values::NadaValue::new_array_non_empty(values)
+            .map_err(|e| ValueError::new_err(&format!("Invalid array: {e}")))?;
+        Ok(Self(value))
+    }
+
+    /// Create a new tuple value.
+    ///
+    /// @param {NadaValue} left - The left element of the tuple
+    /// @param {NadaValue} right - The right element of the tuple
+    /// @return {NadaValue} The tuple containing the values provided
+    /// @throws {Error} if any of the elements is a secret blob.
+    ///
+    /// @example
+    /// const value = NadaValue.new_tuple(NadaValue.new_secret_integer("1"), NadaValue.new_public_integer("2"));
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn new_tuple(left: &NadaValue, right: &NadaValue) -> JsResult<NadaValue> {
+        reject_blobs([&left.0, &right.0])?;
+        let value = nillion_client_core::values::NadaValue::new_tuple(left.0.clone(), right.0.clone())
+            .map_err(|e| ValueError::new_err(&format!("Invalid tuple: {e}")))?;
+        Ok(Self(value))
+    }
+
+    /// Create a new n-tuple value.
+    ///
+    /// Unlike arrays, the elements of an n-tuple can have different types.
+    ///
+    /// @param {NadaValue[]} values - The elements of the n-tuple
+    /// @return {NadaValue} The n-tuple containing the values provided
+    /// @throws {Error} if any of the elements is a secret blob.
+    ///
+    /// @example
+    /// const value = NadaValue.new_n_tuple([NadaValue.new_secret_integer("1"), NadaValue.new_secret_boolean(true)]);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn new_n_tuple(values: Vec<NadaValue>) -> JsResult<NadaValue> {
+        reject_blobs(values.iter().map(|value| &value.0))?;
+        let values = values.into_iter().map(NadaValue::into_inner).collect();
+        let value = nillion_client_core::values::NadaValue::new_n_tuple(values)
+            .map_err(|e| ValueError::new_err(&format!("Invalid n-tuple: {e}")))?;
+        Ok(Self(value))
+    }
+
+    /// Create a new object value.
+    ///
+    /// The object fields are sorted by name.
+    ///
+    /// @param {NadaValues} values - The named fields of the object
+    /// @return {NadaValue} The object containing the values provided
+    /// @throws {Error} if any of the fields is a secret blob.
+    ///
+    /// @example
+    /// const fields = new NadaValues();
+    /// fields.insert("a", NadaValue.new_secret_integer("1"));
+    /// const value = NadaValue.new_object(fields);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn new_object(values: &NadaValues) -> JsResult<NadaValue> {
+        reject_blobs(values.0.values())?;
+        let mut values: Vec<_> = values.0.iter().map(|(name, value)| (name.clone(), value.clone())).collect();
+        values.sort_by(|(left, _), (right, _)| left.cmp(right));
+        let value = nillion_client_core::values::NadaValue::new_object(values.into_iter().collect())
+            .map_err(|e| ValueError::new_err(&format!("Invalid object: {e}")))?;
+        Ok(Self(value))
+    }
+
     /// Convert this value into a byte array.
     ///
     /// This is only valid for secret blob values.
@@ -262,7 +452,26 @@ impl NadaValue {
     /// const value = NadaValue.new_secret_blob([1,0,1,222,21]);
     /// const byteArray = value.to_byte_array();
     #[wasm_bindgen(skip_jsdoc)]
-    pub fn to_byte_array(&self) -> Result<Vec<u8>, JsError> {
+    pub fn to_byte_array(&self) -> Result<Uint8Array, JsError> {
+        // Copy the bytes straight into the javascript array so no copy is left behind in the wasm heap.
+        let bytes = Zeroizing::new(self.bytes()?);
+        Ok(to_byte_array(&bytes))
+    }
+
+    /// Zero out the contents of this value.
+    ///
+    /// Values are wiped automatically when they are freed, this allows wiping them earlier. Any cleartext contents
+    /// are overwritten with zeros in place: blobs and messages keep their length and private keys become zero keys.
+    ///
+    /// @example
+    /// value.wipe();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn wipe(&mut self) {
+        wipe_value(&mut self.0);
+    }
+
+    /// The bytes contained in this value.
+    pub(crate) fn bytes(&self) -> JsResult<Vec<u8>> {
         match &self.0 {
             nillion_client_core::values::NadaValue::SecretBlob(v) => Ok(v.to_vec()),
             nillion_client_core::values::NadaValue::EcdsaPrivateKey(v) => Ok(v.clone().to_be_bytes()),
@@ -337,6 +546,27 @@ impl NadaValue {
         }
     }
 
+    /// Convert this value into a decimal string representation using the given scale.
+    ///
+    /// This is the inverse of `new_secret_rational` and `new_public_rational`, and only works for integer values.
+    /// @param {number} scale - The number of fractional digits the value was created with.
+    /// @return {string} a decimal representation of the underlying numeric value
+    /// @throws {Error} if the value is not an integer or the scale is larger than any prime allows.
+    ///
+    /// @example
+    /// const value = NadaValue.new_secret_rational("12.345", 3, masker.modulo());
+    /// const rational_value = value.to_rational(3); // "12.345"
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_rational(&self, scale: u32) -> Result<String, JsError> {
+        use nillion_client_core::values::NadaValue::*;
+
+        match &self.0 {
+            SecretInteger(value) | Integer(value) => format_rational(&value.to_string(), scale),
+            UnsignedInteger(value) | SecretUnsignedInteger(value) => format_rational(&value.to_string(), scale),
+            _ => Err(JsError::new("value is not an integer")),
+        }
+    }
+
     /// Return the Nada type represented by this instance.
     ///
     /// @example
@@ -344,28 +574,348 @@ impl NadaValue {
     /// console.log(value.type()); // "SecretInteger"
     #[wasm_bindgen(skip_jsdoc)]
     pub fn type_name(&self) -> JsResult<String> {
+        Ok(type_name(&self.0)?.into())
+    }
+
+    /// Get a redacted representation of this value that is safe to log.
+    ///
+    /// This contains the type of the value and, for blobs and compound values, their size but never their contents.
+    ///
+    /// @return {object} A `{ type, size }` record
+    ///
+    /// @example
+    /// console.log(JSON.stringify(NadaValue.new_secret_blob([1, 2, 3]))); // {"type":"SecretBlob","size":3}
+    #[wasm_bindgen(js_name = toJSON, skip_jsdoc)]
+    pub fn to_json(&self) -> JsResult<JsValue> {
+        Ok(JsValue::from(redacted_record(&self.0)?))
+    }
+
+    /// Get a redacted description of this value that is safe to log.
+    ///
+    /// @example
+    /// console.log(`${NadaValue.new_secret_integer("42")}`); // SecretInteger(redacted)
+    #[wasm_bindgen(js_name = toString, skip_jsdoc)]
+    pub fn to_redacted_string(&self) -> JsResult<String> {
+        redacted_string(&self.0)
+    }
+
+    /// Reveal the contents of this value.
+    ///
+    /// @return {object} A `{ type, value }` record containing the cleartext value, in the format used by
+    ///   `NadaValues.to_record`
+    ///
+    /// @example
+    /// const { type, value } = NadaValue.new_secret_integer("42").reveal();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn reveal(&self) -> JsResult<JsValue> {
+        Ok(JsValue::from(self.to_record()?))
+    }
+
+    /// Wrap a digest into an ecdsa digest message.
+    fn digest_message(digest: [u8; 32]) -> NadaValue {
+        Self(nillion_client_core::values::NadaValue::new_ecdsa_digest_message(digest))
+    }
+
+    /// Convert this value into a `{ type, value }` record.
+    ///
+    /// Compound values are converted recursively: arrays, tuples and n-tuples hold a list of records and objects
+    /// hold a record per field.
+    fn to_record(&self) -> JsResult<Object> {
         use nillion_client_core::values::NadaValue::*;
-        let type_str = match self.0 {
-            Integer(_) => "PublicInteger",
-            UnsignedInteger(_) => "PublicUnsignedInteger",
-            Boolean(_) => "PublicBoolean",
-            SecretInteger(_) => "SecretInteger",
-            SecretUnsignedInteger(_) => "SecretUnsignedInteger",
-            SecretBoolean(_) => "SecretBoolean",
-            SecretBlob(_) => "SecretBlob",
-            EcdsaPrivateKey(_) => "EcdsaPrivateKey",
-            EcdsaDigestMessage(_) => "EcdsaDigestMessage",
-            EcdsaSignature(_) => "EcdsaSignature",
-            EcdsaPublicKey(_) => "EcdsaPublicKey",
-            EddsaPrivateKey(_) => "EddsaPrivateKey",
-            EddsaMessage(_) => "EddsaMessage",
-            EddsaSignature(_) => "EddsaSignature",
-            EddsaPublicKey(_) => "EddsaPublicKey",
-            StoreId(_) => "StoreId",
-            _ => Err(JsError::new(&format!("Unsupported type {:?}", self.0)))?,
+
+        let record = Object::new();
+        js_sys::Reflect::set(&record, &JsValue::from("type"), &JsValue::from(&self.type_name()?))
+            .map_err(|e| JsError::new(&format!("Failed to set type: {:?}", e)))?;
+
+        let js_value = match &self.0 {
+            SecretBlob(_)
+            | EcdsaPrivateKey(_)
+            | EcdsaDigestMessage(_)
+            | EcdsaPublicKey(_)
+            | EddsaPrivateKey(_)
+            | EddsaMessage(_)
+            | EddsaPublicKey(_)
+            | StoreId(_) => JsValue::from(self.to_byte_array()?),
+            EcdsaSignature(_) => JsValue::from(self.to_ecdsa_signature()?),
+            EddsaSignature(_) => JsValue::from(self.to_eddsa_signature()?),
+            Array { values, .. } | NTuple { values } => {
+                let records = values
+                    .iter()
+                    .map(|value| NadaValue(value.clone()).to_record().map(JsValue::from))
+                    .collect::<JsResult<Array>>()?;
+                JsValue::from(records)
+            }
+            Tuple { left, right } => {
+                let left = NadaValue(left.as_ref().clone()).to_record()?;
+                let right = NadaValue(right.as_ref().clone()).to_record()?;
+                JsValue::from(Array::of2(&left, &right))
+            }
+            Object { values } => {
+                let fields = Object::new();
+                for (name, value) in values {
+                    let field = NadaValue(value.clone()).to_record()?;
+                    js_sys::Reflect::set(&fields, &JsValue::from(name), &JsValue::from(field))
+                        .map_err(|e| JsError::new(&format!("Failed to set property: {:?}", e)))?;
+                }
+                JsValue::from(fields)
+            }
+            _ => JsValue::from(self.to_integer()?),
+        };
+
+        js_sys::Reflect::set(&record, &JsValue::from("value"), &js_value)
+            .map_err(|e| JsError::new(&format!("Failed to set value: {:?}", e)))?;
+        Ok(record)
+    }
+
+    /// Build a value out of a `{ type, value }` record, as produced by `to_record`.
+    ///
+    /// The path is used to point at the offending value in error messages.
+    fn from_record(record: &JsValue, path: &str) -> JsResult<NadaValue> {
+        let nada_type = js_sys::Reflect::get(record, &JsValue::from("type"))
+            .ok()
+            .and_then(|nada_type| nada_type.as_string())
+            .ok_or_else(|| ValueError::new_err(&format!("{path}: type not found")))?;
+        let value = js_sys::Reflect::get(record, &JsValue::from("value"))
+            .ok()
+            .filter(|value| !value.is_undefined())
+            .ok_or_else(|| ValueError::new_err(&format!("{path}: value not found")))?;
+
+        let value = match nada_type.as_str() {
+            "PublicInteger" => Self::new_public_integer(&record_string(&value)?),
+            "PublicUnsignedInteger" => Self::new_public_unsigned_integer(&record_string(&value)?),
+            "PublicBoolean" => Self::new_public_boolean(record_boolean(&value)?),
+            "SecretInteger" => Self::new_secret_integer(&record_string(&value)?),
+            "SecretUnsignedInteger" => Self::new_secret_unsigned_integer(&record_string(&value)?),
+            "SecretBoolean" => Self::new_secret_boolean(record_boolean(&value)?),
+            "SecretBlob" => Ok(Self::new_secret_blob(record_bytes(&value)?)),
+            "EcdsaPrivateKey" => Self::new_ecdsa_private_key(record_bytes(&value)?),
+            "EcdsaDigestMessage" => Self::new_ecdsa_digest_message(record_bytes(&value)?),
+            "EcdsaSignature" => {
+                Self::new_ecdsa_signature(record_component(&value, "r")?, record_component(&value, "s")?)
+            }
+            "EcdsaPublicKey" => Self::new_ecdsa_public_key(record_bytes(&value)?),
+            "EddsaPrivateKey" => Self::new_eddsa_private_key(record_bytes(&value)?),
+            "EddsaMessage" => Self::new_eddsa_message(record_bytes(&value)?),
+            "EddsaSignature" => {
+                Self::new_eddsa_signature(record_component(&value, "r")?, record_component(&value, "z")?)
+            }
+            "EddsaPublicKey" => Self::new_eddsa_public_key(record_bytes(&value)?),
+            "StoreId" => Self::new_store_id(record_bytes(&value)?),
+            "Array" | "NTuple" => {
+                if !Array::is_array(&value) {
+                    return Err(ValueError::new_err(&format!("{path}: expected an array of records")));
+                }
+                let values = Array::from(&value)
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| Self::from_record(&value, &format!("{path}[{index}]")))
+                    .collect::<JsResult<Vec<_>>>()?;
+                let value = if nada_type == "Array" { Self::new_array(values) } else { Self::new_n_tuple(values) };
+                return value.map_err(|e| error_at(path, e));
+            }
+            "Tuple" => {
+                if !Array::is_array(&value) || Array::from(&value).length() != 2 {
+                    return Err(ValueError::new_err(&format!("{path}: expected an array of 2 records")));
+                }
+                let values = Array::from(&value);
+                let left = Self::from_record(&values.get(0), &format!("{path}[0]"))?;
+                let right = Self::from_record(&values.get(1), &format!("{path}[1]"))?;
+                return Self::new_tuple(&left, &right).map_err(|e| error_at(path, e));
+            }
+            "Object" => {
+                let values = NadaValues::from_record_at(&value, path)?;
+                return Self::new_object(&values).map_err(|e| error_at(path, e));
+            }
+            _ => Err(ValueError::new_err(&format!("unsupported type {nada_type}"))),
         };
-        Ok(type_str.into())
+        value.map_err(|e| error_at(path, e))
+    }
+}
+
+/// The Nada type name of a cleartext value.
+fn type_name(value: &nillion_client_core::values::NadaValue<Clear>) -> JsResult<&'static str> {
+    use nillion_client_core::values::NadaValue::*;
+    let type_str = match value {
+        Integer(_) => "PublicInteger",
+        UnsignedInteger(_) => "PublicUnsignedInteger",
+        Boolean(_) => "PublicBoolean",
+        SecretInteger(_) => "SecretInteger",
+        SecretUnsignedInteger(_) => "SecretUnsignedInteger",
+        SecretBoolean(_) => "SecretBoolean",
+        SecretBlob(_) => "SecretBlob",
+        EcdsaPrivateKey(_) => "EcdsaPrivateKey",
+        EcdsaDigestMessage(_) => "EcdsaDigestMessage",
+        EcdsaSignature(_) => "EcdsaSignature",
+        EcdsaPublicKey(_) => "EcdsaPublicKey",
+        EddsaPrivateKey(_) => "EddsaPrivateKey",
+        EddsaMessage(_) => "EddsaMessage",
+        EddsaSignature(_) => "EddsaSignature",
+        EddsaPublicKey(_) => "EddsaPublicKey",
+        StoreId(_) => "StoreId",
+        Array { .. } => "Array",
+        Tuple { .. } => "Tuple",
+        NTuple { .. } => "NTuple",
+        Object { .. } => "Object",
+        _ => Err(JsError::new(&format!("Unsupported type {:?}", value)))?,
+    };
+    Ok(type_str)
+}
+
+/// The size of a value that can be shown without revealing its contents.
+///
+/// This is the number of bytes for blobs and messages, and the number of elements for compound values.
+fn redacted_size(value: &nillion_client_core::values::NadaValue<Clear>) -> Option<usize> {
+    use nillion_client_core::values::NadaValue::*;
+    match value {
+        SecretBlob(value) | EddsaMessage(value) => Some(value.len()),
+        Array { values, .. } | NTuple { values } => Some(values.len()),
+        Tuple { .. } => Some(2),
+        Object { values } => Some(values.len()),
+        _ => None,
+    }
+}
+
+/// Build a `{ type, size }` record for a value.
+fn redacted_record(value: &nillion_client_core::values::NadaValue<Clear>) -> JsResult<Object> {
+    let record = Object::new();
+    js_sys::Reflect::set(&record, &JsValue::from("type"), &JsValue::from(type_name(value)?))
+        .map_err(|e| JsError::new(&format!("Failed to set type: {:?}", e)))?;
+    if let Some(size) = redacted_size(value) {
+        js_sys::Reflect::set(&record, &JsValue::from("size"), &JsValue::from(size))
+            .map_err(|e| JsError::new(&format!("Failed to set size: {:?}", e)))?;
+    }
+    Ok(record)
+}
+
+/// Describe a value as its type and size.
+fn redacted_string(value: &nillion_client_core::values::NadaValue<Clear>) -> JsResult<String> {
+    let type_name = type_name(value)?;
+    Ok(match redacted_size(value) {
+        Some(size) => format!("{type_name}(redacted, size {size})"),
+        None => format!("{type_name}(redacted)"),
+    })
+}
+
+/// Make node's `util.inspect`, which `console.log` uses, show the redacted `toString` form of values, and make value
+/// collections iterable over their sorted `[name, value]` entries.
+///
+/// Methods can't be named after symbols with `wasm_bindgen` so the hooks are added to the classes' prototypes when the
+/// module is loaded.
+#[wasm_bindgen(start)]
+fn install_symbol_hooks() -> JsResult<()> {
+    let symbol = js_sys::Symbol::for_("nodejs.util.inspect.custom");
+    let instances = [
+        JsValue::from(NadaValue(nillion_client_core::values::NadaValue::new_boolean(false))),
+        JsValue::from(NadaValues(HashMap::new())),
+    ];
+    for instance in instances {
+        let prototype = Object::get_prototype_of(&instance);
+        let to_string = js_sys::Reflect::get(&prototype, &JsValue::from("toString"))
+            .map_err(|_| JsError::new("Failed toString not found"))?;
+        js_sys::Reflect::set(&prototype, &symbol, &to_string)
+            .map_err(|_| JsError::new("Failed to set the inspect hook"))?;
+    }
+
+    let iterator = js_sys::Function::new_no_args("return this.entries()[Symbol.iterator]();");
+    let collections = [JsValue::from(NadaValues(HashMap::new())), JsValue::from(EncryptedNadaValues(HashMap::new()))];
+    for collection in collections {
+        let prototype = Object::get_prototype_of(&collection);
+        js_sys::Reflect::set(&prototype, &js_sys::Symbol::iterator(), &iterator)
+            .map_err(|_| JsError::new("Failed to set the iterator"))?;
+    }
+    Ok(())
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const ITERATORS: &str = r#"
+export interface NadaValues {
+  [Symbol.iterator](): Iterator<[string, NadaValue]>;
+}
+
+export interface EncryptedNadaValues {
+  [Symbol.iterator](): Iterator<[string, object]>;
+}
+"#;
+
+/// Make sure none of the elements of a compound value is a secret blob, since blobs can't be nested.
+fn reject_blobs<'a>(
+    values: impl IntoIterator<Item = &'a nillion_client_core::values::NadaValue<Clear>>,
+) -> JsResult<()> {
+    if values.into_iter().any(|value| matches!(value, nillion_client_core::values::NadaValue::SecretBlob(_))) {
+        return Err(ValueError::new_err("Secret blobs can't be nested in other values"));
+    }
+    Ok(())
+}
+
+/// Overwrite the cleartext contents of a value with zeros, in place.
+///
+/// Private keys are replaced with a zero key, which zeroizes the secret scalar of the old one when it's dropped.
+fn wipe_value(value: &mut nillion_client_core::values::NadaValue<Clear>) {
+    use nillion_client_core::values::NadaValue::*;
+    match value {
+        Integer(value) | SecretInteger(value) => value.zeroize(),
+        UnsignedInteger(value) | SecretUnsignedInteger(value) => value.zeroize(),
+        Boolean(value) | SecretBoolean(value) => value.zeroize(),
+        SecretBlob(value) | EddsaMessage(value) => value.iter_mut().for_each(Zeroize::zeroize),
+        EcdsaDigestMessage(value) => value.zeroize(),
+        EcdsaPrivateKey(value) => {
+            if let Ok(zero) = ThresholdPrivateKey::from_be_bytes(&[0; 32]) {
+                *value = zero;
+            }
+        }
+        EddsaPrivateKey(value) => {
+            if let Ok(zero) = ThresholdPrivateKey::from_le_bytes(&[0; 32]) {
+                *value = zero;
+            }
+        }
+        Array { values, .. } | NTuple { values } => values.iter_mut().for_each(wipe_value),
+        Tuple { left, right } => {
+            wipe_value(left);
+            wipe_value(right);
+        }
+        Object { values } => values.values_mut().for_each(wipe_value),
+        _ => {}
+    }
+}
+
+/// Prefix an error's message with the path of the value that caused it.
+fn error_at(path: &str, error: JsError) -> JsError {
+    let error: js_sys::Error = JsValue::from(error).unchecked_into();
+    let message = String::from(error.message());
+    let message = message.strip_prefix("ValueError: ").unwrap_or(&message);
+    ValueError::new_err(&format!("{path}: {message}"))
+}
+
+fn record_string(value: &JsValue) -> JsResult<String> {
+    value.as_string().ok_or_else(|| ValueError::new_err("expected a string value"))
+}
+
+fn record_boolean(value: &JsValue) -> JsResult<bool> {
+    match value.as_string().as_deref() {
+        Some("true") => Ok(true),
+        Some("false") => Ok(false),
+        _ => value.as_bool().ok_or_else(|| ValueError::new_err("expected a boolean value")),
+    }
+}
+
+fn record_bytes(value: &JsValue) -> JsResult<Vec<u8>> {
+    if !value.is_instance_of::<Uint8Array>() {
+        return Err(ValueError::new_err("expected a Uint8Array value"));
     }
+    Ok(Uint8Array::from(value.clone()).to_vec())
+}
+
+/// Get a signature component, which is a method in `EcdsaSignature`/`EddsaSignature` and a property in plain objects.
+fn record_component(value: &JsValue, name: &str) -> JsResult<Vec<u8>> {
+    let component = js_sys::Reflect::get(value, &JsValue::from(name))
+        .map_err(|_| ValueError::new_err(&format!("signature component {name} not found")))?;
+    let component = match component.dyn_ref::<js_sys::Function>() {
+        Some(getter) => getter
+            .call0(value)
+            .map_err(|_| ValueError::new_err(&format!("failed to get signature component {name}")))?,
+        None => component,
+    };
+    record_bytes(&component).map_err(|_| ValueError::new_err(&format!("invalid signature component {name}")))
 }
 
 fn try_into_scalar(bytes: &[u8], parameter: &str) -> JsResult<NonZero<Scalar<Secp256k1>>> {
@@ -374,8 +924,77 @@ fn try_into_scalar(bytes: &[u8], parameter: &str) -> JsResult<NonZero<Scalar<Sec
         .ok_or_else(|| ValueError::new_err(&format!("Ecdsa signature parameter {parameter}: value cannot be 0")))
 }
 
+/// Parse a decimal string into an integer scaled by `10^scale`.
+fn parse_rational(value: &str, scale: u32, modulo: &EncodedModulo) -> JsResult<BigInt> {
+    let (sign, unsigned) = match value.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", value),
+    };
+    let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if integer.is_empty() && fraction.is_empty() {
+        return Err(ValueError::new_err(&format!("Invalid rational value: {value}")));
+    }
+    if !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+        return Err(ValueError::new_err(&format!("Invalid rational value: {value}")));
+    }
+    // A larger scale can't fit in the prime anyway, rejecting it up front avoids padding huge strings.
+    if scale > modulo.decimal_digits() {
+        return Err(ValueError::new_err(&format!("Rational scale {scale} is larger than the prime allows")));
+    }
+    let fraction = fraction.trim_end_matches('0');
+    let scale = usize::try_from(scale).map_err(|_| ValueError::new_err("Invalid rational scale"))?;
+    if fraction.len() > scale {
+        return Err(ValueError::new_err(&format!("Rational value {value} has more than {scale} fractional digits")));
+    }
+    let scaled = format!("{sign}{integer}{fraction:0<scale$}");
+    let scaled: BigInt =
+        scaled.parse().map_err(|e| ValueError::new_err(&format!("Invalid rational value {value}: {e}")))?;
+    if scaled.magnitude() > &modulo.signed_max() {
+        return Err(ValueError::new_err(&format!("Rational value {value} with scale {scale} overflows the prime")));
+    }
+    Ok(scaled)
+}
+
+/// Format an integer scaled by `10^scale` as a decimal string.
+fn format_rational(value: &str, scale: u32) -> Result<String, JsError> {
+    // No prime has more digits than the 256 bit one, so no value could have been created with a larger scale.
+    let max_scale = EncodedModulo(nillion_client_core::values::EncodedModulo::U256SafePrime).decimal_digits();
+    if scale > max_scale {
+        return Err(JsError::new(&format!("Rational scale {scale} is larger than {max_scale}")));
+    }
+    let (sign, digits) = match value.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", value),
+    };
+    let scale = usize::try_from(scale).map_err(|_| JsError::new("Invalid rational scale"))?;
+    if scale == 0 {
+        return Ok(value.to_string());
+    }
+    // Left pad with zeros so there's always an integer part.
+    let width = scale.saturating_add(1);
+    let digits = format!("{digits:0>width$}");
+    let (integer, fraction) = digits.split_at(digits.len().saturating_sub(scale));
+    Ok(format!("{sign}{integer}.{fraction}"))
+}
+
+impl NadaValue {
+    /// Take the underlying value out, leaving nothing behind to be wiped.
+    pub(crate) fn into_inner(mut self) -> nillion_client_core::values::NadaValue<Clear> {
+        std::mem::replace(&mut self.0, nillion_client_core::values::NadaValue::new_boolean(false))
+    }
+}
+
+impl Drop for NadaValue {
+    fn drop(&mut self) {
+        wipe_value(&mut self.0);
+    }
+}
+
 /// A collection of named values.
-#[wasm_bindgen(inspectable)]
+///
+/// Its `toJSON`, `toString` and node's `util.inspect` representations are redacted so values can be logged safely.
+/// Iterating over it yields the same `[name, value]` pairs as `entries`.
+#[wasm_bindgen]
 #[cfg_attr(test, derive(Debug, Clone, PartialEq))]
 pub struct NadaValues(pub(crate) HashMap<String, nillion_client_core::values::NadaValue<Clear>>);
 
@@ -412,6 +1031,108 @@ impl NadaValues {
         self.0.len()
     }
 
+    /// Get a copy of the value with the given name.
+    ///
+    /// @param {string} name - The name of the value
+    /// @return {NadaValue | undefined} The value, or undefined if there's no value with that name
+    ///
+    /// @example
+    /// const value = values.get("my_value");
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn get(&self, name: &str) -> Option<NadaValue> {
+        self.0.get(name).cloned().map(NadaValue)
+    }
+
+    /// Check whether there is a value with the given name.
+    ///
+    /// @param {string} name - The name of the value
+    ///
+    /// @example
+    /// const exists = values.has("my_value");
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn has(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    /// Remove the value with the given name.
+    ///
+    /// @param {string} name - The name of the value
+    /// @return {NadaValue | undefined} The removed value, or undefined if there was no value with that name
+    ///
+    /// @example
+    /// const value = values.remove("my_value");
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn remove(&mut self, name: &str) -> Option<NadaValue> {
+        self.0.remove(name).map(NadaValue)
+    }
+
+    /// Get the names of the values, sorted.
+    ///
+    /// @example
+    /// const names = values.keys();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn keys(&self) -> Vec<String> {
+        sorted_keys(&self.0)
+    }
+
+    /// Get the `[name, value]` pairs in this collection, sorted by name.
+    ///
+    /// @return {Array<[string, NadaValue]>} The entries of this collection
+    ///
+    /// @example
+    /// for (const [name, value] of values.entries()) {
+    ///   console.log(name, value.type_name());
+    /// }
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn entries(&self) -> Array {
+        sorted_keys(&self.0)
+            .into_iter()
+            .filter_map(|name| {
+                let value = self.0.get(&name)?.clone();
+                Some(JsValue::from(Array::of2(&JsValue::from(name), &JsValue::from(NadaValue(value)))))
+            })
+            .collect()
+    }
+
+    /// Merge the values in another collection into this one.
+    ///
+    /// @param {NadaValues} other - The values to be merged
+    /// @param {MergePolicy} policy - What to do when both collections contain a value with the same name
+    /// @throws {Error} if the policy is `MergePolicy.Error` and there are conflicting names.
+    ///
+    /// @example
+    /// values.merge(otherValues, MergePolicy.Overwrite);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn merge(&mut self, other: &NadaValues, policy: MergePolicy) -> JsResult<()> {
+        merge_values(&mut self.0, &other.0, policy)
+    }
+
+    /// Get the values whose type is one of the given type names.
+    ///
+    /// @param {string[]} type_names - The type names, as returned by `NadaValue.type_name`
+    /// @return {NadaValues} The values matching any of the type names
+    ///
+    /// @example
+    /// const integers = values.filter(["PublicInteger", "SecretInteger"]);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn filter(&self, type_names: Vec<String>) -> JsResult<NadaValues> {
+        let (matching, _) = partition_values(&self.0, &type_names, type_name)?;
+        Ok(Self(matching))
+    }
+
+    /// Split the values in two: the ones whose type is one of the given type names and the rest.
+    ///
+    /// @param {string[]} type_names - The type names, as returned by `NadaValue.type_name`
+    /// @return {NadaValues[]} The values matching any of the type names followed by the ones that don't
+    ///
+    /// @example
+    /// const [publicValues, secretValues] = values.partition(["PublicInteger", "PublicUnsignedInteger", "PublicBoolean"]);
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn partition(&self, type_names: Vec<String>) -> JsResult<Vec<NadaValues>> {
+        let (matching, rest) = partition_values(&self.0, &type_names, type_name)?;
+        Ok(vec![Self(matching), Self(rest)])
+    }
+
     /// Convert NadaValues into a JS object
     ///
     /// @example
@@ -419,48 +1140,183 @@ impl NadaValues {
     /// nadaValues.insert("foo", NadaValue::new_secret_integer("42"));
     /// const values = nadaValues.to_record();
     /// console.log(values); // { foo: { type: "SecretInteger", value: "42" } }
+    ///
+    /// Compound values are nested, e.g. an array of secret integers becomes
+    /// `{ type: "Array", value: [{ type: "SecretInteger", value: "1" }, ...] }`.
     #[wasm_bindgen]
     pub fn to_record(&self) -> JsResult<JsValue> {
         let js_obj = Object::new();
         for (name, value) in &self.0 {
-            let inner_obj = Object::new();
-
-            let wrapped = NadaValue(value.clone());
-            let nada_type = wrapped.type_name()?;
-
-            js_sys::Reflect::set(&inner_obj, &JsValue::from("type"), &JsValue::from(&nada_type))
-                .map_err(|e| JsError::new(&format!("Failed to set type: {:?}", e)))?;
-
-            let js_value = match value {
-                nillion_client_core::values::NadaValue::SecretBlob(_)
-                | nillion_client_core::values::NadaValue::EcdsaPrivateKey(_)
-                | nillion_client_core::values::NadaValue::EcdsaDigestMessage(_)
-                | nillion_client_core::values::NadaValue::EcdsaPublicKey(_)
-                | nillion_client_core::values::NadaValue::EddsaPrivateKey(_)
-                | nillion_client_core::values::NadaValue::EddsaMessage(_)
-                | nillion_client_core::values::NadaValue::EddsaPublicKey(_)
-                | nillion_client_core::values::NadaValue::StoreId(_) => {
-                    let byte_array = wrapped.to_byte_array()?;
-                    let uint8_array = to_byte_array(&byte_array);
-                    JsValue::from(uint8_array)
-                }
-                nillion_client_core::values::NadaValue::EcdsaSignature(_) => {
-                    JsValue::from(wrapped.to_ecdsa_signature()?)
-                }
-                nillion_client_core::values::NadaValue::EddsaSignature(_) => {
-                    JsValue::from(wrapped.to_eddsa_signature()?)
-                }
-                _ => JsValue::from(wrapped.to_integer()?),
-            };
-
-            js_sys::Reflect::set(&inner_obj, &JsValue::from("value"), &js_value)
-                .map_err(|e| JsError::new(&format!("Failed to set value: {:?}", e)))?;
-
+            let inner_obj = NadaValue(value.clone()).to_record()?;
             js_sys::Reflect::set(&js_obj, &JsValue::from(name), &JsValue::from(inner_obj))
                 .map_err(|e| JsError::new(&format!("Failed to set property: {:?}", e)))?;
         }
         Ok(JsValue::from(js_obj))
     }
+
+    /// Get a redacted representation of these values that is safe to log.
+    ///
+    /// @return {object} An object containing a `{ type, size }` record per value
+    ///
+    /// @example
+    /// console.log(JSON.stringify(values)); // {"foo":{"type":"SecretInteger"}}
+    #[wasm_bindgen(js_name = toJSON, skip_jsdoc)]
+    pub fn to_json(&self) -> JsResult<JsValue> {
+        let js_obj = Object::new();
+        for (name, value) in &self.0 {
+            js_sys::Reflect::set(&js_obj, &JsValue::from(name), &JsValue::from(redacted_record(value)?))
+                .map_err(|e| JsError::new(&format!("Failed to set property: {:?}", e)))?;
+        }
+        Ok(JsValue::from(js_obj))
+    }
+
+    /// Get a redacted description of these values that is safe to log.
+    ///
+    /// @example
+    /// console.log(`${values}`); // NadaValues { foo: SecretInteger(redacted) }
+    #[wasm_bindgen(js_name = toString, skip_jsdoc)]
+    pub fn to_redacted_string(&self) -> JsResult<String> {
+        let values = sorted_keys(&self.0)
+            .into_iter()
+            .filter_map(|name| self.0.get(&name).map(|value| Ok(format!("{name}: {}", redacted_string(value)?))))
+            .collect::<JsResult<Vec<_>>>()?;
+        Ok(format!("NadaValues {{ {} }}", values.join(", ")))
+    }
+
+    /// Reveal the contents of these values.
+    ///
+    /// This is the same as `to_record`, it exists to make revealing cleartext values explicit.
+    ///
+    /// @example
+    /// const record = values.reveal();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn reveal(&self) -> JsResult<JsValue> {
+        self.to_record()
+    }
+
+    /// Zero out the contents of all values and remove them.
+    ///
+    /// Values are wiped automatically when they are freed, this allows wiping them earlier.
+    ///
+    /// @example
+    /// values.wipe();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn wipe(&mut self) {
+        self.0.values_mut().for_each(wipe_value);
+        self.0.clear();
+    }
+
+    /// Convert a JS object produced by `to_record` back into NadaValues
+    ///
+    /// @param {object} record - The record, as returned by `to_record`
+    /// @return {NadaValues} The values contained in the record
+    /// @throws {Error} if any of the values is invalid, the error message names the offending key.
+    ///
+    /// @example
+    /// const values = NadaValues.from_record({ foo: { type: "SecretInteger", value: "42" } });
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn from_record(record: &JsValue) -> JsResult<NadaValues> {
+        Self::from_record_at(record, "")
+    }
+
+    fn from_record_at(record: &JsValue, path: &str) -> JsResult<NadaValues> {
+        if !record.is_object() {
+            return Err(ValueError::new_err(&format!(
+                "NadaValues cannot be created from the current value {record:?}"
+            )));
+        }
+        let mut values = HashMap::new();
+        let keys = js_sys::Reflect::own_keys(record).map_err(|_| JsError::new("Failed reading object keys"))?;
+        for key in keys.iter() {
+            let name =
+                key.as_string().ok_or_else(|| JsError::new(&format!("Unexpected key {key:?}, expected a string")))?;
+            let key_path = if path.is_empty() { name.clone() } else { format!("{path}.{name}") };
+            let value = js_sys::Reflect::get(record, &key)
+                .map_err(|_| ValueError::new_err(&format!("{key_path}: value not found")))?;
+            let value = NadaValue::from_record(&value, &key_path)?;
+            values.insert(name, value.into_inner());
+        }
+        Ok(Self(values))
+    }
+}
+
+impl Drop for NadaValues {
+    fn drop(&mut self) {
+        self.0.values_mut().for_each(wipe_value);
+    }
+}
+
+/// What to do when merging two collections of values that contain a value with the same name.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Fail the merge.
+    Error,
+    /// Keep the value that is already in the collection.
+    KeepExisting,
+    /// Replace the value in the collection with the one being merged.
+    Overwrite,
+}
+
+fn merge_values<T: Clone>(
+    target: &mut HashMap<String, T>,
+    other: &HashMap<String, T>,
+    policy: MergePolicy,
+) -> JsResult<()> {
+    if policy == MergePolicy::Error {
+        let conflicts: Vec<_> = sorted_keys(other).into_iter().filter(|name| target.contains_key(name)).collect();
+        if !conflicts.is_empty() {
+            return Err(ValueError::new_err(&format!("conflicting value names: {}", conflicts.join(", "))));
+        }
+    }
+    for (name, value) in other {
+        if policy == MergePolicy::KeepExisting && target.contains_key(name) {
+            continue;
+        }
+        target.insert(name.clone(), value.clone());
+    }
+    Ok(())
+}
+
+type Partition<T> = (HashMap<String, T>, HashMap<String, T>);
+
+fn partition_values<T, N, F>(values: &HashMap<String, T>, type_names: &[String], name_of: F) -> JsResult<Partition<T>>
+where
+    T: Clone,
+    N: AsRef<str>,
+    F: Fn(&T) -> JsResult<N>,
+{
+    let mut matching = HashMap::new();
+    let mut rest = HashMap::new();
+    for (name, value) in values {
+        let value_type = name_of(value)?;
+        let target =
+            if type_names.iter().any(|type_name| type_name == value_type.as_ref()) { &mut matching } else { &mut rest };
+        target.insert(name.clone(), value.clone());
+    }
+    Ok((matching, rest))
+}
+
+/// All the subsets of `size` elements out of some items, keeping their order.
+fn combinations<T: Clone>(items: &[T], size: usize) -> Vec<Vec<T>> {
+    let Some(remaining) = size.checked_sub(1) else {
+        return vec![Vec::new()];
+    };
+    let Some((first, rest)) = items.split_first() else {
+        return Vec::new();
+    };
+    let mut subsets = combinations(rest, remaining);
+    for subset in &mut subsets {
+        subset.insert(0, first.clone());
+    }
+    subsets.extend(combinations(rest, size));
+    subsets
+}
+
+fn sorted_keys<T>(values: &HashMap<String, T>) -> Vec<String> {
+    let mut keys: Vec<_> = values.keys().cloned().collect();
+    keys.sort();
+    keys
 }
 
 /// A ecdsa signature
@@ -468,9 +1324,9 @@ impl NadaValues {
 #[derive(Clone)]
 pub struct EcdsaSignature {
     /// r component of the signature in binary format
-    r: Vec<u8>,
+    pub(crate) r: Vec<u8>,
     /// s component of the signature in binary format
-    s: Vec<u8>,
+    pub(crate) s: Vec<u8>,
 }
 
 #[wasm_bindgen]
@@ -497,9 +1353,9 @@ impl EcdsaSignature {
 #[derive(Clone)]
 pub struct EddsaSignature {
     /// r component of the signature in binary format
-    r: Vec<u8>,
+    pub(crate) r: Vec<u8>,
     /// z component of the signature in binary format
-    z: Vec<u8>,
+    pub(crate) z: Vec<u8>,
 }
 
 #[wasm_bindgen]
@@ -531,7 +1387,7 @@ impl EddsaSignature {
 /// A party identifier.
 #[wasm_bindgen(inspectable)]
 #[derive(Clone)]
-pub struct PartyId(Vec<u8>);
+pub struct PartyId(pub(crate) Vec<u8>);
 
 #[wasm_bindgen]
 impl PartyId {
@@ -558,11 +1414,40 @@ pub fn to_byte_array(bytes: &[u8]) -> Uint8Array {
 #[derive(Copy, Clone)]
 pub struct EncodedModulo(nillion_client_core::values::EncodedModulo);
 
+impl EncodedModulo {
+    /// The largest magnitude of signed integers under this modulo.
+    ///
+    /// Signed integers are encoded in the range `[-(p-1)/2, (p-1)/2]`, this is `(p-1)/2` for each safe prime.
+    fn signed_max(&self) -> BigUint {
+        use nillion_client_core::values::EncodedModulo::*;
+        let digits: &[u8] = match self.0 {
+            // p = 18446744072637906947
+            U64SafePrime => b"9223372036318953473",
+            // p = 340282366920938463463374607429104828419
+            U128SafePrime => b"170141183460469231731687303714552414209",
+            // p = 115792089237316195423570985008687907853269984665640564039457584007911397392387
+            U256SafePrime => b"57896044618658097711785492504343953926634992332820282019728792003955698696193",
+        };
+        // The constants are valid decimal numbers, the tests make sure of it.
+        BigUint::parse_bytes(digits, 10).unwrap_or_default()
+    }
+
+    /// The number of decimal digits of the prime, which bounds the scale of rational values.
+    fn decimal_digits(&self) -> u32 {
+        use nillion_client_core::values::EncodedModulo::*;
+        match self.0 {
+            U64SafePrime => 20,
+            U128SafePrime => 39,
+            U256SafePrime => 78,
+        }
+    }
+}
+
 /// A secret masker.
 ///
 /// This allows masking and unmasking secrets.
 #[wasm_bindgen]
-pub struct SecretMasker(nillion_client_core::values::SecretMasker, EncodedModulo);
+pub struct SecretMasker(nillion_client_core::values::SecretMasker, EncodedModulo, u64);
 
 #[wasm_bindgen]
 impl SecretMasker {
@@ -571,7 +1456,7 @@ impl SecretMasker {
         let parties = parties.into_iter().map(|p| nillion_client_core::values::PartyId::from(p.0)).collect();
         let masker = nillion_client_core::values::SecretMasker::new_64_bit_safe_prime(polynomial_degree, parties)
             .map_err(|e| ValueError::new_err(&format!("failed to create secret masker: {e}")))?;
-        Ok(Self(masker, EncodedModulo(nillion_client_core::values::EncodedModulo::U64SafePrime)))
+        Ok(Self(masker, EncodedModulo(nillion_client_core::values::EncodedModulo::U64SafePrime), polynomial_degree))
     }
 
     /// Construct a new masker that uses a 128 bit safe prime under the hood.
@@ -579,7 +1464,7 @@ impl SecretMasker {
         let parties = parties.into_iter().map(|p| nillion_client_core::values::PartyId::from(p.0)).collect();
         let masker = nillion_client_core::values::SecretMasker::new_128_bit_safe_prime(polynomial_degree, parties)
             .map_err(|e| ValueError::new_err(&format!("failed to create secret masker: {e}")))?;
-        Ok(Self(masker, EncodedModulo(nillion_client_core::values::EncodedModulo::U128SafePrime)))
+        Ok(Self(masker, EncodedModulo(nillion_client_core::values::EncodedModulo::U128SafePrime), polynomial_degree))
     }
 
     /// Construct a new masker that uses a 256 bit safe prime under the hood.
@@ -587,12 +1472,18 @@ impl SecretMasker {
         let parties = parties.into_iter().map(|p| nillion_client_core::values::PartyId::from(p.0)).collect();
         let masker = nillion_client_core::values::SecretMasker::new_256_bit_safe_prime(polynomial_degree, parties)
             .map_err(|e| ValueError::new_err(&format!("failed to create secret masker: {e}")))?;
-        Ok(Self(masker, EncodedModulo(nillion_client_core::values::EncodedModulo::U256SafePrime)))
+        Ok(Self(masker, EncodedModulo(nillion_client_core::values::EncodedModulo::U256SafePrime), polynomial_degree))
     }
 
     /// Mask a set of values.
-    pub fn mask(&self, values: NadaValues) -> JsResult<Vec<PartyShares>> {
-        let shares = self.0.mask(values.0).map_err(|e| ValueError::new_err(&format!("failed to mask values: {e}")))?;
+    ///
+    /// The values are consumed, so they can't be used after being masked.
+    pub fn mask(&self, mut values: NadaValues) -> JsResult<Vec<PartyShares>> {
+        // Hand the values over rather than cloning them so no extra cleartext copy is made.
+        let shares = self
+            .0
+            .mask(std::mem::take(&mut values.0))
+            .map_err(|e| ValueError::new_err(&format!("failed to mask values: {e}")))?;
         let shares = shares
             .into_iter()
             .map(|(party, shares)| PartyShares {
@@ -614,6 +1505,92 @@ impl SecretMasker {
         Ok(NadaValues(values))
     }
 
+    /// Unmask a set of encrypted values, tolerating and identifying parties whose shares are inconsistent.
+    ///
+    /// Any `polynomial_degree + 1` shares determine the values, so the ones above that are used to cross check them:
+    /// the values are decoded out of every subset of that size and the result backed by the most parties wins. Up to
+    /// `(shares - polynomial_degree - 1) / 2` faulty parties can be tolerated this way.
+    ///
+    /// Since the number of subsets grows exponentially at most 16 parties are supported. Values that can't be decoded
+    /// out of `polynomial_degree + 1` shares, like additive private key shares which need every party, can't be
+    /// cross checked and are rejected, use `unmask` for those.
+    ///
+    /// @param {Array<PartyShares>} shares - The shares of each party
+    /// @return {RobustUnmaskResult} The unmasked values along with the parties whose shares disagree with them
+    /// @throws {Error} if a party is repeated, there are more than 16 parties, the values can't be decoded out of a
+    /// subset of the shares or there are too many faulty parties to decide which values are right.
+    ///
+    /// @example
+    /// const result = masker.unmask_robust(shares);
+    /// const faultyParties = result.faulty_parties;
+    /// const values = result.take_values();
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn unmask_robust(&self, shares: Vec<PartyShares>) -> JsResult<RobustUnmaskResult> {
+        let threshold = usize::try_from(self.2)
+            .ok()
+            .and_then(|degree| degree.checked_add(1))
+            .ok_or_else(|| ValueError::new_err("polynomial degree is too large"))?;
+        let parties: Vec<PartyId> = shares.iter().map(|party_shares| party_shares.party.clone()).collect();
+        for (index, party) in parties.iter().enumerate() {
+            if parties.iter().skip(index.saturating_add(1)).any(|other| other.0 == party.0) {
+                return Err(ValueError::new_err("failed to unmask shares: the same party was provided twice"));
+            }
+        }
+        if shares.len() > MAX_ROBUST_UNMASK_PARTIES {
+            return Err(ValueError::new_err(&format!(
+                "failed to unmask shares: robust unmasking supports at most {MAX_ROBUST_UNMASK_PARTIES} parties"
+            )));
+        }
+        if shares.len() <= threshold {
+            return Ok(RobustUnmaskResult { values: self.unmask(shares)?, faulty_parties: Vec::new() });
+        }
+
+        // Decode every minimal subset and keep track of the parties backing each distinct result.
+        let mut candidates: Vec<(NadaValues, Vec<usize>)> = Vec::new();
+        let indexes: Vec<usize> = (0..shares.len()).collect();
+        for subset in combinations(&indexes, threshold) {
+            let subset_shares = subset.iter().filter_map(|index| shares.get(*index).cloned()).collect();
+            let Ok(values) = self.unmask(subset_shares) else {
+                continue;
+            };
+            match candidates.iter_mut().find(|(candidate, _)| candidate.0 == values.0) {
+                Some((_, backers)) => {
+                    for index in subset {
+                        if !backers.contains(&index) {
+                            backers.push(index);
+                        }
+                    }
+                }
+                None => candidates.push((values, subset)),
+            }
+        }
+        let best = candidates.iter().enumerate().max_by_key(|(_, (_, backers))| backers.len()).map(|(index, _)| index);
+        let Some(best) = best else {
+            // No subset can be decoded on its own, e.g. additive key shares need every party.
+            return Err(ValueError::new_err(
+                "failed to unmask shares: the values can't be decoded out of a subset of the shares to cross check them",
+            ));
+        };
+        let (values, backers) = candidates.swap_remove(best);
+
+        // Decoding is only unambiguous if the backers outnumber the faulty parties by more than the degree.
+        let faulty = shares.len().saturating_sub(backers.len());
+        if backers.len().saturating_sub(faulty) < threshold {
+            return Err(ValueError::new_err(&format!(
+                "failed to unmask shares: {faulty} of {} parties disagree, which is too many to tell which ones are \
+                 faulty",
+                shares.len()
+            )));
+        }
+        let faulty_parties = parties
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !backers.contains(index))
+            .map(|(_, party)| party)
+            .collect();
+        Ok(RobustUnmaskResult { values, faulty_parties })
+    }
+
     /// Classify the given cleartext values.
     ///
     /// This allows getting the totals per value type which is a required parameter when storing values.
@@ -650,9 +1627,10 @@ pub struct NadaValuesClassification {
 
 /// The shares for a party.
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct PartyShares {
-    party: PartyId,
-    shares: EncryptedNadaValues,
+    pub(crate) party: PartyId,
+    pub(crate) shares: EncryptedNadaValues,
 }
 
 #[wasm_bindgen]
@@ -676,11 +1654,36 @@ impl PartyShares {
     }
 }
 
+/// The result of unmasking values robustly.
+#[wasm_bindgen]
+pub struct RobustUnmaskResult {
+    values: NadaValues,
+    faulty_parties: Vec<PartyId>,
+}
+
+#[wasm_bindgen]
+impl RobustUnmaskResult {
+    /// Take the unmasked values out of this result.
+    ///
+    /// The values are moved rather than copied, so calling this again returns an empty collection.
+    pub fn take_values(&mut self) -> NadaValues {
+        NadaValues(std::mem::take(&mut self.values.0))
+    }
+
+    /// Get the parties whose shares disagree with the unmasked values.
+    #[wasm_bindgen(getter)]
+    pub fn faulty_parties(&self) -> Vec<PartyId> {
+        self.faulty_parties.clone()
+    }
+}
+
 /// A set of encrypted nada values.
+///
+/// Iterating over it yields the same `[name, value]` pairs as `entries`.
 #[wasm_bindgen]
 #[derive(Clone)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
-pub struct EncryptedNadaValues(HashMap<String, nillion_client_core::values::NadaValue<Encrypted<Encoded>>>);
+pub struct EncryptedNadaValues(pub(crate) HashMap<String, nillion_client_core::values::NadaValue<Encrypted<Encoded>>>);
 
 #[wasm_bindgen]
 impl EncryptedNadaValues {
@@ -689,108 +1692,231 @@ impl EncryptedNadaValues {
     pub fn to_js_object(&self) -> JsResult<JsValue> {
         let js_obj = Object::new();
         for (name, nada_value) in &self.0 {
-            let inner_obj = Object::new();
-            let nada_type = nada_value.to_type().to_string();
-
-            js_sys::Reflect::set(&inner_obj, &JsValue::from("type"), &JsValue::from(&nada_type))
-                .map_err(|e| JsError::new(&format!("Failed to set type: {:?}", e)))?;
-
-            use nillion_client_core::values::NadaValue as CoreNadaValue;
-            match nada_value {
-                CoreNadaValue::Integer(value)
-                | CoreNadaValue::UnsignedInteger(value)
-                | CoreNadaValue::Boolean(value) => {
-                    let js_value = JsValue::from(to_byte_array(value.as_bytes()));
-                    js_sys::Reflect::set(&inner_obj, &JsValue::from("value"), &js_value)
-                        .map_err(|_| JsError::new("Failed to set value"))?;
-                }
-
-                CoreNadaValue::ShamirShareInteger(value)
-                | CoreNadaValue::ShamirShareUnsignedInteger(value)
-                | CoreNadaValue::ShamirShareBoolean(value) => {
-                    let js_value = JsValue::from(to_byte_array(value.as_bytes()));
-                    js_sys::Reflect::set(&inner_obj, &JsValue::from("value"), &js_value)
-                        .map_err(|_| JsError::new("Failed to set value"))?;
-                }
-
-                CoreNadaValue::SecretBlob(value) => {
-                    let shares = value
-                        .value
-                        .iter()
-                        .map(|share| JsValue::from(to_byte_array(share.as_bytes())))
-                        .collect::<Array>();
-                    js_sys::Reflect::set(&inner_obj, &JsValue::from("shares"), &shares)
-                        .map_err(|_| JsError::new("Failed to set shares"))?;
-
-                    let js_original_size = JsValue::from(value.unencoded_size.to_string());
-                    js_sys::Reflect::set(&inner_obj, &JsValue::from("originalSize"), &js_original_size)
-                        .map_err(|_| JsError::new("Failed to set originalSize"))?;
-                }
-                CoreNadaValue::EcdsaPrivateKey(value) => {
-                    Self::private_key_to_json(&inner_obj, value)?;
-                }
-                CoreNadaValue::EcdsaDigestMessage(value) => {
-                    let js_value = JsValue::from(to_byte_array(value));
-                    js_sys::Reflect::set(&inner_obj, &JsValue::from("digest"), &js_value)
-                        .map_err(|_| JsError::new("Failed to set digest"))?;
-                }
-                CoreNadaValue::EcdsaSignature(value) => {
-                    let r = value.r.clone().to_le_bytes();
-                    let js_r = JsValue::from(to_byte_array(&r));
-                    js_sys::Reflect::set(&inner_obj, &JsValue::from("r"), &js_r)
-                        .map_err(|_| JsError::new("Failed to set r"))?;
-
-                    let sigma = value.sigma.clone().to_le_bytes();
-                    let js_sigma = JsValue::from(to_byte_array(&sigma));
-                    js_sys::Reflect::set(&inner_obj, &JsValue::from("sigma"), &js_sigma)
-                        .map_err(|_| JsError::new("Failed to set sigma"))?;
-                }
-                CoreNadaValue::EcdsaPublicKey(value) => {
-                    let js_public_key = JsValue::from(to_byte_array(&value.0));
-                    js_sys::Reflect::set(&inner_obj, &JsValue::from("publicKey"), &js_public_key)
-                        .map_err(|_| JsError::new("Failed to set publicKey"))?;
-                }
-                CoreNadaValue::EddsaPrivateKey(value) => {
-                    Self::private_key_to_json(&inner_obj, value)?;
-                }
-                CoreNadaValue::EddsaMessage(value) => {
-                    let js_value = JsValue::from(to_byte_array(value));
-                    js_sys::Reflect::set(&inner_obj, &JsValue::from("message"), &js_value)
-                        .map_err(|_| JsError::new("Failed to set message"))?;
-                }
-                CoreNadaValue::EddsaSignature(value) => {
-                    let signature = value.to_bytes();
-                    let js_signature = JsValue::from(to_byte_array(&signature));
-                    js_sys::Reflect::set(&inner_obj, &JsValue::from("signature"), &js_signature)
-                        .map_err(|_| JsError::new("Failed to set signature"))?;
-                }
-                CoreNadaValue::EddsaPublicKey(value) => {
-                    let js_public_key = JsValue::from(to_byte_array(value));
-                    js_sys::Reflect::set(&inner_obj, &JsValue::from("publicKey"), &js_public_key)
-                        .map_err(|_| JsError::new("Failed to set publicKey"))?;
-                }
-                CoreNadaValue::StoreId(store_id) => {
-                    let js_store_id = JsValue::from(to_byte_array(store_id));
-                    js_sys::Reflect::set(&inner_obj, &JsValue::from("storeId"), &js_store_id)
-                        .map_err(|_| JsError::new("Failed to set value"))?;
-                }
-                CoreNadaValue::SecretInteger(_)
-                | CoreNadaValue::SecretUnsignedInteger(_)
-                | CoreNadaValue::SecretBoolean(_)
-                | CoreNadaValue::NTuple { .. }
-                | CoreNadaValue::Object { .. }
-                | CoreNadaValue::Array { .. }
-                | CoreNadaValue::Tuple { .. } => {
-                    return Err(JsError::new(&format!("Type {} can not be converted to protobuf", nada_type)));
-                }
-            };
-
+            let inner_obj = Self::value_to_js_object(nada_value)?;
             js_sys::Reflect::set(&js_obj, &JsValue::from(name), &JsValue::from(inner_obj))
                 .map_err(|e| JsError::new(&format!("Failed to set property: {:?}", e)))?;
         }
         Ok(JsValue::from(js_obj))
     }
 
+    /// Get the number of values.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Get the value with the given name as a JS object, in the same format as `to_js_object` uses.
+    ///
+    /// @param {string} name - The name of the value
+    /// @return {object | undefined} The value, or undefined if there's no value with that name
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn get(&self, name: &str) -> JsResult<JsValue> {
+        match self.0.get(name) {
+            Some(value) => Ok(JsValue::from(Self::value_to_js_object(value)?)),
+            None => Ok(JsValue::undefined()),
+        }
+    }
+
+    /// Check whether there is a value with the given name.
+    pub fn has(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    /// Remove the value with the given name.
+    ///
+    /// @param {string} name - The name of the value
+    /// @return {object | undefined} The removed value as a JS object in the same format as `to_js_object` uses, or
+    ///   undefined if there was no value with that name
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn remove(&mut self, name: &str) -> JsResult<Option<Object>> {
+        // Convert the value before removing it so it isn't lost if the conversion fails.
+        let value = self.0.get(name).map(Self::value_to_js_object).transpose()?;
+        self.0.remove(name);
+        Ok(value)
+    }
+
+    /// Get the names of the values, sorted.
+    pub fn keys(&self) -> Vec<String> {
+        sorted_keys(&self.0)
+    }
+
+    /// Get the `[name, value]` pairs in this collection, sorted by name.
+    ///
+    /// Values are JS objects in the same format as `to_js_object` uses.
+    ///
+    /// @return {Array<[string, object]>} The entries of this collection
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn entries(&self) -> JsResult<Array> {
+        sorted_keys(&self.0)
+            .into_iter()
+            .filter_map(|name| self.0.get(&name).map(|value| (name, value)))
+            .map(|(name, value)| {
+                let value = Self::value_to_js_object(value)?;
+                Ok(JsValue::from(Array::of2(&JsValue::from(name), &value)))
+            })
+            .collect()
+    }
+
+    /// Merge the values in another collection into this one.
+    ///
+    /// @param {EncryptedNadaValues} other - The values to be merged
+    /// @param {MergePolicy} policy - What to do when both collections contain a value with the same name
+    /// @throws {Error} if the policy is `MergePolicy.Error` and there are conflicting names.
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn merge(&mut self, other: &EncryptedNadaValues, policy: MergePolicy) -> JsResult<()> {
+        merge_values(&mut self.0, &other.0, policy)
+    }
+
+    /// Get the values whose type is one of the given type names.
+    ///
+    /// @param {string[]} type_names - The type names, as found in the `type` field of `to_js_object`
+    /// @return {EncryptedNadaValues} The values matching any of the type names
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn filter(&self, type_names: Vec<String>) -> JsResult<EncryptedNadaValues> {
+        let (matching, _) = partition_values(&self.0, &type_names, |value| Ok(Self::type_name(value)))?;
+        Ok(Self(matching))
+    }
+
+    /// Split the values in two: the ones whose type is one of the given type names and the rest.
+    ///
+    /// @param {string[]} type_names - The type names, as found in the `type` field of `to_js_object`
+    /// @return {EncryptedNadaValues[]} The values matching any of the type names followed by the ones that don't
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn partition(&self, type_names: Vec<String>) -> JsResult<Vec<EncryptedNadaValues>> {
+        let (matching, rest) = partition_values(&self.0, &type_names, |value| Ok(Self::type_name(value)))?;
+        Ok(vec![Self(matching), Self(rest)])
+    }
+
+    fn type_name(nada_value: &nillion_client_core::values::NadaValue<Encrypted<Encoded>>) -> String {
+        use nillion_client_core::values::NadaValue as CoreNadaValue;
+        match nada_value {
+            CoreNadaValue::Array { .. } => "Array".to_string(),
+            CoreNadaValue::Tuple { .. } => "Tuple".to_string(),
+            CoreNadaValue::NTuple { .. } => "NTuple".to_string(),
+            CoreNadaValue::Object { .. } => "Object".to_string(),
+            _ => nada_value.to_type().to_string(),
+        }
+    }
+
+    pub(crate) fn value_to_js_object(
+        nada_value: &nillion_client_core::values::NadaValue<Encrypted<Encoded>>,
+    ) -> JsResult<Object> {
+        use nillion_client_core::values::NadaValue as CoreNadaValue;
+
+        let inner_obj = Object::new();
+        let nada_type = Self::type_name(nada_value);
+
+        js_sys::Reflect::set(&inner_obj, &JsValue::from("type"), &JsValue::from(&nada_type))
+            .map_err(|e| JsError::new(&format!("Failed to set type: {:?}", e)))?;
+
+        match nada_value {
+            CoreNadaValue::Integer(value) | CoreNadaValue::UnsignedInteger(value) | CoreNadaValue::Boolean(value) => {
+                let js_value = JsValue::from(to_byte_array(value.as_bytes()));
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("value"), &js_value)
+                    .map_err(|_| JsError::new("Failed to set value"))?;
+            }
+
+            CoreNadaValue::ShamirShareInteger(value)
+            | CoreNadaValue::ShamirShareUnsignedInteger(value)
+            | CoreNadaValue::ShamirShareBoolean(value) => {
+                let js_value = JsValue::from(to_byte_array(value.as_bytes()));
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("value"), &js_value)
+                    .map_err(|_| JsError::new("Failed to set value"))?;
+            }
+
+            CoreNadaValue::SecretBlob(value) => {
+                let shares =
+                    value.value.iter().map(|share| JsValue::from(to_byte_array(share.as_bytes()))).collect::<Array>();
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("shares"), &shares)
+                    .map_err(|_| JsError::new("Failed to set shares"))?;
+
+                let js_original_size = JsValue::from(value.unencoded_size.to_string());
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("originalSize"), &js_original_size)
+                    .map_err(|_| JsError::new("Failed to set originalSize"))?;
+            }
+            CoreNadaValue::EcdsaPrivateKey(value) => {
+                Self::private_key_to_json(&inner_obj, value)?;
+            }
+            CoreNadaValue::EcdsaDigestMessage(value) => {
+                let js_value = JsValue::from(to_byte_array(value));
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("digest"), &js_value)
+                    .map_err(|_| JsError::new("Failed to set digest"))?;
+            }
+            CoreNadaValue::EcdsaSignature(value) => {
+                let r = value.r.clone().to_le_bytes();
+                let js_r = JsValue::from(to_byte_array(&r));
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("r"), &js_r)
+                    .map_err(|_| JsError::new("Failed to set r"))?;
+
+                let sigma = value.sigma.clone().to_le_bytes();
+                let js_sigma = JsValue::from(to_byte_array(&sigma));
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("sigma"), &js_sigma)
+                    .map_err(|_| JsError::new("Failed to set sigma"))?;
+            }
+            CoreNadaValue::EcdsaPublicKey(value) => {
+                let js_public_key = JsValue::from(to_byte_array(&value.0));
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("publicKey"), &js_public_key)
+                    .map_err(|_| JsError::new("Failed to set publicKey"))?;
+            }
+            CoreNadaValue::EddsaPrivateKey(value) => {
+                Self::private_key_to_json(&inner_obj, value)?;
+            }
+            CoreNadaValue::EddsaMessage(value) => {
+                let js_value = JsValue::from(to_byte_array(value));
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("message"), &js_value)
+                    .map_err(|_| JsError::new("Failed to set message"))?;
+            }
+            CoreNadaValue::EddsaSignature(value) => {
+                let signature = value.to_bytes();
+                let js_signature = JsValue::from(to_byte_array(&signature));
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("signature"), &js_signature)
+                    .map_err(|_| JsError::new("Failed to set signature"))?;
+            }
+            CoreNadaValue::EddsaPublicKey(value) => {
+                let js_public_key = JsValue::from(to_byte_array(value));
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("publicKey"), &js_public_key)
+                    .map_err(|_| JsError::new("Failed to set publicKey"))?;
+            }
+            CoreNadaValue::StoreId(store_id) => {
+                let js_store_id = JsValue::from(to_byte_array(store_id));
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("storeId"), &js_store_id)
+                    .map_err(|_| JsError::new("Failed to set value"))?;
+            }
+            CoreNadaValue::Array { values, .. } | CoreNadaValue::NTuple { values } => {
+                let js_values = values
+                    .iter()
+                    .map(|value| Self::value_to_js_object(value).map(JsValue::from))
+                    .collect::<JsResult<Array>>()?;
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("values"), &js_values)
+                    .map_err(|_| JsError::new("Failed to set values"))?;
+            }
+            CoreNadaValue::Tuple { left, right } => {
+                let js_left = Self::value_to_js_object(left)?;
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("left"), &js_left)
+                    .map_err(|_| JsError::new("Failed to set left"))?;
+                let js_right = Self::value_to_js_object(right)?;
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("right"), &js_right)
+                    .map_err(|_| JsError::new("Failed to set right"))?;
+            }
+            CoreNadaValue::Object { values } => {
+                let js_values = Object::new();
+                for (name, value) in values {
+                    let js_value = Self::value_to_js_object(value)?;
+                    js_sys::Reflect::set(&js_values, &JsValue::from(name), &js_value)
+                        .map_err(|_| JsError::new(&format!("Failed to set field {name}")))?;
+                }
+                js_sys::Reflect::set(&inner_obj, &JsValue::from("values"), &js_values)
+                    .map_err(|_| JsError::new("Failed to set values"))?;
+            }
+            CoreNadaValue::SecretInteger(_)
+            | CoreNadaValue::SecretUnsignedInteger(_)
+            | CoreNadaValue::SecretBoolean(_) => {
+                return Err(JsError::new(&format!("Type {} can not be converted to protobuf", nada_type)));
+            }
+        };
+        Ok(inner_obj)
+    }
+
     fn private_key_to_json<T: Curve>(obj: &Object, private_key: &ThresholdPrivateKeyShare<T>) -> JsResult<()> {
         let private_key = private_key.as_inner();
         // i
@@ -817,13 +1943,28 @@ impl EncryptedNadaValues {
             .collect::<Array>();
         js_sys::Reflect::set(obj, &JsValue::from("publicShares"), &js_public_shares)
             .map_err(|_| JsError::new("Failed to set publicShares"))?;
+        // vss_setup
+        if let Some(vss_setup) = &private_key.key_info.vss_setup {
+            let js_vss_setup = Object::new();
+            js_sys::Reflect::set(
+                &js_vss_setup,
+                &JsValue::from("minSigners"),
+                &JsValue::from(vss_setup.min_signers.to_string()),
+            )
+            .map_err(|_| JsError::new("Failed to set minSigners"))?;
+            let js_indexes =
+                vss_setup.I.iter().map(|index| JsValue::from(to_byte_array(&index.to_le_bytes()))).collect::<Array>();
+            js_sys::Reflect::set(&js_vss_setup, &JsValue::from("indexes"), &js_indexes)
+                .map_err(|_| JsError::new("Failed to set indexes"))?;
+            js_sys::Reflect::set(obj, &JsValue::from("vssSetup"), &js_vss_setup)
+                .map_err(|_| JsError::new("Failed to set vssSetup"))?;
+        }
         Ok(())
     }
 
     /// Convert a JS object into a EncryptedNadaValues
     #[wasm_bindgen]
     pub fn from_js_object(js_object: &JsValue, modulo: EncodedModulo) -> JsResult<EncryptedNadaValues> {
-        use nillion_client_core::values::NadaValue as CoreNadaValue;
         if !js_object.is_object() {
             return Err(JsError::new(&format!(
                 "EncryptedNadaValues cannot be created from the current value {:?}",
@@ -837,149 +1978,8 @@ impl EncryptedNadaValues {
             if let Some(name) = key.as_string() {
                 let value = js_sys::Reflect::get(js_object, &key)
                     .map_err(|_| JsError::new(&format!("Value {name} not found")))?;
-                let nada_type = js_sys::Reflect::get(&value, &JsValue::from("type"))
-                    .map_err(|_| JsError::new("Failed value type not found"))?;
-                if let Some(nada_type_name) = nada_type.as_string() {
-                    let nada_value = match nada_type_name.as_str() {
-                        "Integer" => {
-                            let js_value = js_sys::Reflect::get(&value, &JsValue::from("value"))
-                                .map_err(|_| JsError::new("Failed value not found"))?;
-                            CoreNadaValue::new_integer(EncodedModularNumber::new_unchecked(
-                                Uint8Array::from(js_value).to_vec(),
-                                modulo.0,
-                            ))
-                        }
-                        "UnsignedInteger" => {
-                            let js_value = js_sys::Reflect::get(&value, &JsValue::from("value"))
-                                .map_err(|_| JsError::new("Failed value not found"))?;
-                            CoreNadaValue::new_unsigned_integer(EncodedModularNumber::new_unchecked(
-                                Uint8Array::from(js_value).to_vec(),
-                                modulo.0,
-                            ))
-                        }
-                        "Boolean" => {
-                            let js_value = js_sys::Reflect::get(&value, &JsValue::from("value"))
-                                .map_err(|_| JsError::new("Failed value not found"))?;
-                            CoreNadaValue::new_boolean(EncodedModularNumber::new_unchecked(
-                                Uint8Array::from(js_value).to_vec(),
-                                modulo.0,
-                            ))
-                        }
-                        "ShamirShareInteger" => {
-                            let js_value = js_sys::Reflect::get(&value, &JsValue::from("value"))
-                                .map_err(|_| JsError::new("Failed value not found"))?;
-                            CoreNadaValue::new_shamir_share_integer(EncodedModularNumber::new_unchecked(
-                                Uint8Array::from(js_value).to_vec(),
-                                modulo.0,
-                            ))
-                        }
-                        "ShamirShareUnsignedInteger" => {
-                            let js_value = js_sys::Reflect::get(&value, &JsValue::from("value"))
-                                .map_err(|_| JsError::new("Failed value not found"))?;
-                            CoreNadaValue::new_shamir_share_unsigned_integer(EncodedModularNumber::new_unchecked(
-                                Uint8Array::from(js_value).to_vec(),
-                                modulo.0,
-                            ))
-                        }
-                        "ShamirShareBoolean" => {
-                            let js_value = js_sys::Reflect::get(&value, &JsValue::from("value"))
-                                .map_err(|_| JsError::new("Failed value not found"))?;
-                            CoreNadaValue::new_shamir_share_boolean(EncodedModularNumber::new_unchecked(
-                                Uint8Array::from(js_value).to_vec(),
-                                modulo.0,
-                            ))
-                        }
-                        "SecretBlob" => {
-                            let js_shares = js_sys::Reflect::get(&value, &JsValue::from("shares"))
-                                .map_err(|_| JsError::new("Failed shares not found"))?;
-                            let shares = Array::from(&js_shares)
-                                .to_vec()
-                                .into_iter()
-                                .map(|share| {
-                                    EncodedModularNumber::new_unchecked(Uint8Array::from(share).to_vec(), modulo.0)
-                                })
-                                .collect::<Vec<_>>();
-                            let js_unencoded_size = js_sys::Reflect::get(&value, &JsValue::from("originalSize"))
-                                .map_err(|_| JsError::new("Failed originalSize not found"))?
-                                .as_string()
-                                .unwrap_or_default();
-                            let unencoded_size = u64::from_str(&js_unencoded_size)
-                                .map_err(|_| JsError::new("Invalid blob original size"))?;
-                            CoreNadaValue::new_secret_blob(BlobPrimitiveType { value: shares, unencoded_size })
-                        }
-                        "EcdsaPrivateKey" => CoreNadaValue::new_ecdsa_private_key(Self::json_to_private_key(&value)?),
-                        "EcdsaDigestMessage" => {
-                            let js_digest = js_sys::Reflect::get(&value, &JsValue::from("digest"))
-                                .map_err(|_| JsError::new("Failed digest not found"))?;
-                            let digest: [u8; 32] = Uint8Array::from(js_digest)
-                                .to_vec()
-                                .try_into()
-                                .map_err(|_| JsError::new("ecdsa message digest must be 32 bytes"))?;
-                            CoreNadaValue::new_ecdsa_digest_message(digest)
-                        }
-                        "EcdsaSignature" => {
-                            let js_r = js_sys::Reflect::get(&value, &JsValue::from("r"))
-                                .map_err(|_| JsError::new("Failed r not found"))?;
-                            let r = Uint8Array::from(js_r).to_vec();
-
-                            let js_sigma = js_sys::Reflect::get(&value, &JsValue::from("sigma"))
-                                .map_err(|_| JsError::new("Failed sigma not found"))?;
-                            let sigma = Uint8Array::from(js_sigma).to_vec();
-
-                            CoreNadaValue::new_ecdsa_signature(EcdsaSignatureShare {
-                                r: Scalar::from_le_bytes(&r).map_err(|_| JsError::new("ecdsa scalar r is invalid"))?,
-                                sigma: Scalar::from_le_bytes(&sigma)
-                                    .map_err(|_| JsError::new("ecdsa scalar sigma is invalid"))?,
-                            })
-                        }
-                        "EcdsaPublicKey" => {
-                            let js_public_key = js_sys::Reflect::get(&value, &JsValue::from("publicKey"))
-                                .map_err(|_| JsError::new("Failed publicKey not found"))?;
-                            let public_key: [u8; 33] = Uint8Array::from(js_public_key)
-                                .to_vec()
-                                .try_into()
-                                .map_err(|_| JsError::new("ecdsa public key must be 33 bytes"))?;
-                            CoreNadaValue::new_ecdsa_public_key::<EcdsaPublicKeyArray>(public_key.into())
-                        }
-                        "EddsaPrivateKey" => CoreNadaValue::new_eddsa_private_key(Self::json_to_private_key(&value)?),
-                        "EddsaMessage" => {
-                            let js_message = js_sys::Reflect::get(&value, &JsValue::from("message"))
-                                .map_err(|_| JsError::new("Failed eddsa messge not found"))?;
-                            let message = Uint8Array::from(js_message).to_vec();
-                            CoreNadaValue::new_eddsa_message(message)
-                        }
-                        "EddsaSignature" => {
-                            let js_signature = js_sys::Reflect::get(&value, &JsValue::from("signature"))
-                                .map_err(|_| JsError::new("Failed signature not found"))?;
-                            let signature = Uint8Array::from(js_signature).to_vec();
-
-                            let signature = signature::EddsaSignature::from_bytes(&signature)?;
-                            CoreNadaValue::new_eddsa_signature(signature)
-                        }
-                        "EddsaPublicKey" => {
-                            let js_public_key = js_sys::Reflect::get(&value, &JsValue::from("publicKey"))
-                                .map_err(|_| JsError::new("Failed publicKey not found"))?;
-                            let public_key: [u8; 32] = Uint8Array::from(js_public_key)
-                                .to_vec()
-                                .try_into()
-                                .map_err(|_| JsError::new("eddsa public key must be 32 bytes"))?;
-                            CoreNadaValue::new_eddsa_public_key(public_key)
-                        }
-                        "StoreId" => {
-                            let js_store_id = js_sys::Reflect::get(&value, &JsValue::from("storeId"))
-                                .map_err(|_| JsError::new("Failed storeId not found"))?;
-                            let store_id: [u8; 16] = Uint8Array::from(js_store_id)
-                                .to_vec()
-                                .try_into()
-                                .map_err(|_| JsError::new("store id must be 16 bytes"))?;
-                            CoreNadaValue::new_store_id(store_id)
-                        }
-                        _ => Err(JsError::new(&format!("Unsupported type {:?}", nada_type_name)))?,
-                    };
-                    nada_values.insert(name, nada_value);
-                } else {
-                    return Err(JsError::new(&format!("Unexpected type {:?}, expected a string", nada_type)));
-                }
+                let nada_value = Self::value_from_js_object(&value, modulo)?;
+                nada_values.insert(name, nada_value);
             } else {
                 return Err(JsError::new(&format!("Unexpected key {:?}, expected a string", js_object)));
             }
@@ -987,7 +1987,200 @@ impl EncryptedNadaValues {
         Ok(EncryptedNadaValues(nada_values))
     }
 
-    fn json_to_private_key<T: Curve>(value: &JsValue) -> JsResult<ThresholdPrivateKeyShare<T>> {
+    fn value_from_js_object(
+        value: &JsValue,
+        modulo: EncodedModulo,
+    ) -> JsResult<nillion_client_core::values::NadaValue<Encrypted<Encoded>>> {
+        use nillion_client_core::values::NadaValue as CoreNadaValue;
+        let nada_type = js_sys::Reflect::get(value, &JsValue::from("type"))
+            .map_err(|_| JsError::new("Failed value type not found"))?;
+        let Some(nada_type_name) = nada_type.as_string() else {
+            return Err(JsError::new(&format!("Unexpected type {:?}, expected a string", nada_type)));
+        };
+        let nada_value = match nada_type_name.as_str() {
+            "Integer" => {
+                let js_value = js_sys::Reflect::get(value, &JsValue::from("value"))
+                    .map_err(|_| JsError::new("Failed value not found"))?;
+                CoreNadaValue::new_integer(EncodedModularNumber::new_unchecked(
+                    Uint8Array::from(js_value).to_vec(),
+                    modulo.0,
+                ))
+            }
+            "UnsignedInteger" => {
+                let js_value = js_sys::Reflect::get(value, &JsValue::from("value"))
+                    .map_err(|_| JsError::new("Failed value not found"))?;
+                CoreNadaValue::new_unsigned_integer(EncodedModularNumber::new_unchecked(
+                    Uint8Array::from(js_value).to_vec(),
+                    modulo.0,
+                ))
+            }
+            "Boolean" => {
+                let js_value = js_sys::Reflect::get(value, &JsValue::from("value"))
+                    .map_err(|_| JsError::new("Failed value not found"))?;
+                CoreNadaValue::new_boolean(EncodedModularNumber::new_unchecked(
+                    Uint8Array::from(js_value).to_vec(),
+                    modulo.0,
+                ))
+            }
+            "ShamirShareInteger" => {
+                let js_value = js_sys::Reflect::get(value, &JsValue::from("value"))
+                    .map_err(|_| JsError::new("Failed value not found"))?;
+                CoreNadaValue::new_shamir_share_integer(EncodedModularNumber::new_unchecked(
+                    Uint8Array::from(js_value).to_vec(),
+                    modulo.0,
+                ))
+            }
+            "ShamirShareUnsignedInteger" => {
+                let js_value = js_sys::Reflect::get(value, &JsValue::from("value"))
+                    .map_err(|_| JsError::new("Failed value not found"))?;
+                CoreNadaValue::new_shamir_share_unsigned_integer(EncodedModularNumber::new_unchecked(
+                    Uint8Array::from(js_value).to_vec(),
+                    modulo.0,
+                ))
+            }
+            "ShamirShareBoolean" => {
+                let js_value = js_sys::Reflect::get(value, &JsValue::from("value"))
+                    .map_err(|_| JsError::new("Failed value not found"))?;
+                CoreNadaValue::new_shamir_share_boolean(EncodedModularNumber::new_unchecked(
+                    Uint8Array::from(js_value).to_vec(),
+                    modulo.0,
+                ))
+            }
+            "SecretBlob" => {
+                let js_shares = js_sys::Reflect::get(value, &JsValue::from("shares"))
+                    .map_err(|_| JsError::new("Failed shares not found"))?;
+                let shares = Array::from(&js_shares)
+                    .to_vec()
+                    .into_iter()
+                    .map(|share| EncodedModularNumber::new_unchecked(Uint8Array::from(share).to_vec(), modulo.0))
+                    .collect::<Vec<_>>();
+                let js_unencoded_size = js_sys::Reflect::get(value, &JsValue::from("originalSize"))
+                    .map_err(|_| JsError::new("Failed originalSize not found"))?
+                    .as_string()
+                    .unwrap_or_default();
+                let unencoded_size =
+                    u64::from_str(&js_unencoded_size).map_err(|_| JsError::new("Invalid blob original size"))?;
+                CoreNadaValue::new_secret_blob(BlobPrimitiveType { value: shares, unencoded_size })
+            }
+            "EcdsaPrivateKey" => CoreNadaValue::new_ecdsa_private_key(Self::json_to_private_key(value)?),
+            "EcdsaDigestMessage" => {
+                let js_digest = js_sys::Reflect::get(value, &JsValue::from("digest"))
+                    .map_err(|_| JsError::new("Failed digest not found"))?;
+                let digest: [u8; 32] = Uint8Array::from(js_digest)
+                    .to_vec()
+                    .try_into()
+                    .map_err(|_| JsError::new("ecdsa message digest must be 32 bytes"))?;
+                CoreNadaValue::new_ecdsa_digest_message(digest)
+            }
+            "EcdsaSignature" => {
+                let js_r =
+                    js_sys::Reflect::get(value, &JsValue::from("r")).map_err(|_| JsError::new("Failed r not found"))?;
+                let r = Uint8Array::from(js_r).to_vec();
+
+                let js_sigma = js_sys::Reflect::get(value, &JsValue::from("sigma"))
+                    .map_err(|_| JsError::new("Failed sigma not found"))?;
+                let sigma = Uint8Array::from(js_sigma).to_vec();
+
+                CoreNadaValue::new_ecdsa_signature(EcdsaSignatureShare {
+                    r: Scalar::from_le_bytes(&r).map_err(|_| JsError::new("ecdsa scalar r is invalid"))?,
+                    sigma: Scalar::from_le_bytes(&sigma).map_err(|_| JsError::new("ecdsa scalar sigma is invalid"))?,
+                })
+            }
+            "EcdsaPublicKey" => {
+                let js_public_key = js_sys::Reflect::get(value, &JsValue::from("publicKey"))
+                    .map_err(|_| JsError::new("Failed publicKey not found"))?;
+                let public_key: [u8; 33] = Uint8Array::from(js_public_key)
+                    .to_vec()
+                    .try_into()
+                    .map_err(|_| JsError::new("ecdsa public key must be 33 bytes"))?;
+                CoreNadaValue::new_ecdsa_public_key::<EcdsaPublicKeyArray>(public_key.into())
+            }
+            "EddsaPrivateKey" => CoreNadaValue::new_eddsa_private_key(Self::json_to_private_key(value)?),
+            "EddsaMessage" => {
+                let js_message = js_sys::Reflect::get(value, &JsValue::from("message"))
+                    .map_err(|_| JsError::new("Failed eddsa messge not found"))?;
+                let message = Uint8Array::from(js_message).to_vec();
+                CoreNadaValue::new_eddsa_message(message)
+            }
+            "EddsaSignature" => {
+                let js_signature = js_sys::Reflect::get(value, &JsValue::from("signature"))
+                    .map_err(|_| JsError::new("Failed signature not found"))?;
+                let signature = Uint8Array::from(js_signature).to_vec();
+
+                let signature = signature::EddsaSignature::from_bytes(&signature)?;
+                CoreNadaValue::new_eddsa_signature(signature)
+            }
+            "EddsaPublicKey" => {
+                let js_public_key = js_sys::Reflect::get(value, &JsValue::from("publicKey"))
+                    .map_err(|_| JsError::new("Failed publicKey not found"))?;
+                let public_key: [u8; 32] = Uint8Array::from(js_public_key)
+                    .to_vec()
+                    .try_into()
+                    .map_err(|_| JsError::new("eddsa public key must be 32 bytes"))?;
+                CoreNadaValue::new_eddsa_public_key(public_key)
+            }
+            "StoreId" => {
+                let js_store_id = js_sys::Reflect::get(value, &JsValue::from("storeId"))
+                    .map_err(|_| JsError::new("Failed storeId not found"))?;
+                let store_id: [u8; 16] = Uint8Array::from(js_store_id)
+                    .to_vec()
+                    .try_into()
+                    .map_err(|_| JsError::new("store id must be 16 bytes"))?;
+                CoreNadaValue::new_store_id(store_id)
+            }
+            "Array" => {
+                let js_values = js_sys::Reflect::get(value, &JsValue::from("values"))
+                    .map_err(|_| JsError::new("Failed array values not found"))?;
+                let values = Array::from(&js_values)
+                    .to_vec()
+                    .iter()
+                    .map(|value| Self::value_from_js_object(value, modulo))
+                    .collect::<JsResult<Vec<_>>>()?;
+                CoreNadaValue::new_array_non_empty(values).map_err(|e| JsError::new(&format!("Invalid array: {e}")))?
+            }
+            "Tuple" => {
+                let js_left = js_sys::Reflect::get(value, &JsValue::from("left"))
+                    .map_err(|_| JsError::new("Failed tuple left not found"))?;
+                let js_right = js_sys::Reflect::get(value, &JsValue::from("right"))
+                    .map_err(|_| JsError::new("Failed tuple right not found"))?;
+                let left = Self::value_from_js_object(&js_left, modulo)?;
+                let right = Self::value_from_js_object(&js_right, modulo)?;
+                CoreNadaValue::new_tuple(left, right).map_err(|e| JsError::new(&format!("Invalid tuple: {e}")))?
+            }
+            "NTuple" => {
+                let js_values = js_sys::Reflect::get(value, &JsValue::from("values"))
+                    .map_err(|_| JsError::new("Failed n-tuple values not found"))?;
+                let values = Array::from(&js_values)
+                    .to_vec()
+                    .iter()
+                    .map(|value| Self::value_from_js_object(value, modulo))
+                    .collect::<JsResult<Vec<_>>>()?;
+                CoreNadaValue::new_n_tuple(values).map_err(|e| JsError::new(&format!("Invalid n-tuple: {e}")))?
+            }
+            "Object" => {
+                let js_values = js_sys::Reflect::get(value, &JsValue::from("values"))
+                    .map_err(|_| JsError::new("Failed object values not found"))?;
+                let mut values = Vec::new();
+                let keys =
+                    js_sys::Reflect::own_keys(&js_values).map_err(|_| JsError::new("Failed reading object keys"))?;
+                for key in keys.to_vec() {
+                    let name = key
+                        .as_string()
+                        .ok_or_else(|| JsError::new(&format!("Unexpected key {:?}, expected a string", key)))?;
+                    let js_value = js_sys::Reflect::get(&js_values, &key)
+                        .map_err(|_| JsError::new(&format!("Field {name} not found")))?;
+                    values.push((name, Self::value_from_js_object(&js_value, modulo)?));
+                }
+                values.sort_by(|(left, _), (right, _)| left.cmp(right));
+                CoreNadaValue::new_object(values.into_iter().collect())
+                    .map_err(|e| JsError::new(&format!("Invalid object: {e}")))?
+            }
+            _ => Err(JsError::new(&format!("Unsupported type {:?}", nada_type_name)))?,
+        };
+        Ok(nada_value)
+    }
+
+    pub(crate) fn json_to_private_key<T: Curve>(value: &JsValue) -> JsResult<ThresholdPrivateKeyShare<T>> {
         // i
         let js_i = js_sys::Reflect::get(value, &JsValue::from("i"))
             .map_err(|_| JsError::new("Failed i not found"))?
@@ -1002,34 +2195,321 @@ impl EncryptedNadaValues {
             .map_err(|_| JsError::new("Failed sharedPublicKey not found"))?;
         let js_public_shares = js_sys::Reflect::get(value, &JsValue::from("publicShares"))
             .map_err(|_| JsError::new("Failed publicShares not found"))?;
-        let key_info = DirtyKeyInfo {
-            curve: CurveName::new(),
-            shared_public_key: non_zero_point_from_js_value(js_shared_public_key)?,
-            public_shares: Array::from(&js_public_shares)
+        let shared_public_key = non_zero_point_from_js_value(js_shared_public_key)?;
+        let public_shares = Array::from(&js_public_shares)
+            .to_vec()
+            .into_iter()
+            .map(non_zero_point_from_js_value)
+            .collect::<Result<_, _>>()?;
+        // vss_setup, only present for shares of a polynomial rather than additive shares
+        let js_vss_setup = js_sys::Reflect::get(value, &JsValue::from("vssSetup"))
+            .map_err(|_| JsError::new("Failed reading vssSetup"))?;
+        let vss_setup = if js_vss_setup.is_undefined() || js_vss_setup.is_null() {
+            None
+        } else {
+            let js_min_signers = js_sys::Reflect::get(&js_vss_setup, &JsValue::from("minSigners"))
+                .map_err(|_| JsError::new("Failed minSigners not found"))?
+                .as_string()
+                .unwrap_or_default();
+            let min_signers = u16::from_str(&js_min_signers).map_err(|_| JsError::new("Invalid minSigners"))?;
+            let js_indexes = js_sys::Reflect::get(&js_vss_setup, &JsValue::from("indexes"))
+                .map_err(|_| JsError::new("Failed indexes not found"))?;
+            let indexes = Array::from(&js_indexes)
                 .to_vec()
                 .into_iter()
-                .map(non_zero_point_from_js_value)
-                .collect::<Result<_, _>>()?,
-            vss_setup: None,
+                .map(non_zero_scalar_from_js_value)
+                .collect::<Result<_, _>>()?;
+            Some(VssSetup { min_signers, I: indexes })
         };
+        Self::build_private_key(i, x, shared_public_key, public_shares, vss_setup)
+    }
 
+    pub(crate) fn build_private_key<T: Curve>(
+        i: u16,
+        x: NonZero<SecretScalar<T>>,
+        shared_public_key: NonZero<Point<T>>,
+        public_shares: Vec<NonZero<Point<T>>>,
+        vss_setup: Option<VssSetup<T>>,
+    ) -> JsResult<ThresholdPrivateKeyShare<T>> {
+        let key_info = DirtyKeyInfo { curve: CurveName::new(), shared_public_key, public_shares, vss_setup };
         let share = DirtyCoreKeyShare { i, key_info, x }
             .validate()
             .map_err(|e| JsError::new(&format!("invalid ecdsa private key: {e:?}")))?;
         Ok(ThresholdPrivateKeyShare::new(share))
     }
+
+    /// Encode each of these values as a protobuf `nillion.values.v1.value.NamedValue` message, sorted by name.
+    ///
+    /// @return {Uint8Array[]} The encoded values, one message per value
+    /// @throws {Error} if a value has no protobuf representation: n-tuples, objects, empty arrays and private key
+    /// shares with a VSS setup.
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn to_proto_values(&self) -> JsResult<Array> {
+        let values = Array::new();
+        for (name, value) in self.proto_values()? {
+            let bytes = proto::NamedValue { name, value: Some(value) }.encode_to_vec();
+            values.push(&Uint8Array::from(bytes.as_slice()));
+        }
+        Ok(values)
+    }
+
+    /// Decode protobuf `NamedValue` messages, as produced by `to_proto_values`.
+    ///
+    /// @param {Uint8Array[]} values - The encoded values, one message per value
+    /// @param {EncodedModulo} modulo - The modulo of the masker used to mask the values
+    /// @return {EncryptedNadaValues} The decoded values
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn from_proto_values(values: &Array, modulo: EncodedModulo) -> JsResult<EncryptedNadaValues> {
+        let mut nada_values = HashMap::new();
+        for bytes in values.iter() {
+            let bytes = record_bytes(&bytes)?;
+            let proto::NamedValue { name, value } = proto::NamedValue::decode(bytes.as_slice())
+                .map_err(|e| ValueError::new_err(&format!("failed to decode value: {e}")))?;
+            let value = value.ok_or_else(|| ValueError::new_err(&format!("{name}: value not found")))?;
+            let value = Self::value_from_proto(value, modulo).map_err(|e| error_at(&name, e))?;
+            nada_values.insert(name, value);
+        }
+        Ok(EncryptedNadaValues(nada_values))
+    }
+
+    /// Get the size the nodes account these values as: the length of each name plus that of its encoded protobuf
+    /// value.
+    ///
+    /// @return {bigint} The size of the values
+    /// @throws {Error} if a value has no protobuf representation.
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn proto_size(&self) -> JsResult<u64> {
+        let size = self.proto_values()?.iter().fold(0usize, |size, (name, value)| {
+            // Names are measured in UTF-16 code units, like JS string lengths.
+            size.saturating_add(name.encode_utf16().count()).saturating_add(value.encoded_len())
+        });
+        Ok(size as u64)
+    }
+
+    /// Convert these values to protobuf, sorted by name.
+    fn proto_values(&self) -> JsResult<Vec<(String, proto::Value)>> {
+        let mut values = Vec::new();
+        for name in sorted_keys(&self.0) {
+            let Some(value) = self.0.get(&name) else {
+                continue;
+            };
+            let value = Self::value_to_proto(value).map_err(|e| error_at(&name, e))?;
+            values.push((name, value));
+        }
+        Ok(values)
+    }
+
+    fn value_to_proto(
+        nada_value: &nillion_client_core::values::NadaValue<Encrypted<Encoded>>,
+    ) -> JsResult<proto::Value> {
+        use nillion_client_core::values::NadaValue as CoreNadaValue;
+        use proto::value::Value;
+
+        let value = match nada_value {
+            CoreNadaValue::Integer(value) => {
+                Value::PublicInteger(proto::PublicInteger { value: value.as_bytes().to_vec() })
+            }
+            CoreNadaValue::UnsignedInteger(value) => {
+                Value::PublicUnsignedInteger(proto::PublicInteger { value: value.as_bytes().to_vec() })
+            }
+            CoreNadaValue::Boolean(value) => {
+                Value::PublicBoolean(proto::PublicInteger { value: value.as_bytes().to_vec() })
+            }
+            CoreNadaValue::ShamirShareInteger(value) => {
+                Value::ShamirShareInteger(proto::ShamirShare { value: value.as_bytes().to_vec() })
+            }
+            CoreNadaValue::ShamirShareUnsignedInteger(value) => {
+                Value::ShamirShareUnsignedInteger(proto::ShamirShare { value: value.as_bytes().to_vec() })
+            }
+            CoreNadaValue::ShamirShareBoolean(value) => {
+                Value::ShamirShareBoolean(proto::ShamirShare { value: value.as_bytes().to_vec() })
+            }
+            CoreNadaValue::SecretBlob(value) => Value::ShamirSharesBlob(proto::ShamirSharesBlob {
+                shares: value
+                    .value
+                    .iter()
+                    .map(|share| proto::ShamirShare { value: share.as_bytes().to_vec() })
+                    .collect(),
+                original_size: value.unencoded_size,
+            }),
+            CoreNadaValue::EcdsaPrivateKey(value) => Value::EcdsaPrivateKeyShare(Self::private_key_to_proto(value)?),
+            CoreNadaValue::EcdsaDigestMessage(value) => {
+                Value::EcdsaMessageDigest(proto::EcdsaMessageDigest { digest: value.to_vec() })
+            }
+            CoreNadaValue::EcdsaSignature(value) => Value::EcdsaSignatureShare(proto::EcdsaSignatureShare {
+                r: value.r.clone().to_le_bytes().to_vec(),
+                sigma: value.sigma.clone().to_le_bytes().to_vec(),
+            }),
+            CoreNadaValue::EcdsaPublicKey(value) => {
+                Value::EcdsaPublicKey(proto::PublicKey { public_key: value.0.to_vec() })
+            }
+            CoreNadaValue::EddsaPrivateKey(value) => Value::EddsaPrivateKeyShare(Self::private_key_to_proto(value)?),
+            CoreNadaValue::EddsaMessage(value) => Value::EddsaMessage(proto::EddsaMessage { message: value.to_vec() }),
+            CoreNadaValue::EddsaSignature(value) => {
+                Value::EddsaSignature(proto::EddsaSignature { signature: value.to_bytes().to_vec() })
+            }
+            CoreNadaValue::EddsaPublicKey(value) => {
+                Value::EddsaPublicKey(proto::PublicKey { public_key: value.to_vec() })
+            }
+            CoreNadaValue::StoreId(value) => Value::StoreId(proto::StoreId { store_id: value.to_vec() }),
+            CoreNadaValue::Array { values, .. } => {
+                // The element type is taken from the first element, an empty array would lose it and couldn't be
+                // decoded back.
+                if values.is_empty() {
+                    return Err(JsError::new("Empty arrays can not be converted to protobuf"));
+                }
+                let values = values.iter().map(Self::value_to_proto).collect::<JsResult<Vec<_>>>()?;
+                let inner_type = values.first().and_then(proto::Value::value_type);
+                Value::Array(proto::Array { values, inner_type })
+            }
+            CoreNadaValue::Tuple { left, right } => Value::Tuple(Box::new(proto::Tuple {
+                left: Some(Box::new(Self::value_to_proto(left)?)),
+                right: Some(Box::new(Self::value_to_proto(right)?)),
+            })),
+            // The node protobufs have no messages for n-tuples and objects yet.
+            CoreNadaValue::NTuple { .. } | CoreNadaValue::Object { .. } => {
+                return Err(JsError::new(&format!(
+                    "Type {} is not supported by the node protobufs",
+                    Self::type_name(nada_value)
+                )));
+            }
+            CoreNadaValue::SecretInteger(_)
+            | CoreNadaValue::SecretUnsignedInteger(_)
+            | CoreNadaValue::SecretBoolean(_) => {
+                return Err(JsError::new(&format!(
+                    "Type {} can not be converted to protobuf",
+                    Self::type_name(nada_value)
+                )));
+            }
+        };
+        Ok(proto::Value { value: Some(value) })
+    }
+
+    fn private_key_to_proto<T: Curve>(private_key: &ThresholdPrivateKeyShare<T>) -> JsResult<proto::PrivateKeyShare> {
+        let private_key = private_key.as_inner();
+        // The protobuf message has no field for the VSS setup, encoding it would silently turn the share into an
+        // additive one.
+        if private_key.key_info.vss_setup.is_some() {
+            return Err(JsError::new("private key shares with a VSS setup can not be converted to protobuf"));
+        }
+        Ok(proto::PrivateKeyShare {
+            i: private_key.i.into(),
+            x: private_key.x.clone().into_inner().as_ref().to_le_bytes().to_vec(),
+            shared_public_key: private_key.key_info.shared_public_key.to_bytes(true).to_vec(),
+            public_shares: private_key.key_info.public_shares.iter().map(|s| s.to_bytes(true).to_vec()).collect(),
+        })
+    }
+
+    fn value_from_proto(
+        value: proto::Value,
+        modulo: EncodedModulo,
+    ) -> JsResult<nillion_client_core::values::NadaValue<Encrypted<Encoded>>> {
+        use nillion_client_core::values::NadaValue as CoreNadaValue;
+        use proto::value::Value;
+
+        let number = |bytes: Vec<u8>| EncodedModularNumber::new_unchecked(bytes, modulo.0);
+        let value = value.value.ok_or_else(|| JsError::new("Failed value not found"))?;
+        let nada_value = match value {
+            Value::PublicInteger(value) => CoreNadaValue::new_integer(number(value.value)),
+            Value::PublicUnsignedInteger(value) => CoreNadaValue::new_unsigned_integer(number(value.value)),
+            Value::PublicBoolean(value) => CoreNadaValue::new_boolean(number(value.value)),
+            Value::ShamirShareInteger(value) => CoreNadaValue::new_shamir_share_integer(number(value.value)),
+            Value::ShamirShareUnsignedInteger(value) => {
+                CoreNadaValue::new_shamir_share_unsigned_integer(number(value.value))
+            }
+            Value::ShamirShareBoolean(value) => CoreNadaValue::new_shamir_share_boolean(number(value.value)),
+            Value::ShamirSharesBlob(blob) => {
+                let shares = blob.shares.into_iter().map(|share| number(share.value)).collect();
+                CoreNadaValue::new_secret_blob(BlobPrimitiveType { value: shares, unencoded_size: blob.original_size })
+            }
+            Value::EcdsaPrivateKeyShare(share) => {
+                CoreNadaValue::new_ecdsa_private_key(Self::proto_to_private_key(share)?)
+            }
+            Value::EcdsaMessageDigest(message) => {
+                let digest: [u8; 32] =
+                    message.digest.try_into().map_err(|_| JsError::new("ecdsa message digest must be 32 bytes"))?;
+                CoreNadaValue::new_ecdsa_digest_message(digest)
+            }
+            Value::EcdsaSignatureShare(share) => CoreNadaValue::new_ecdsa_signature(EcdsaSignatureShare {
+                r: Scalar::from_le_bytes(&share.r).map_err(|_| JsError::new("ecdsa scalar r is invalid"))?,
+                sigma: Scalar::from_le_bytes(&share.sigma)
+                    .map_err(|_| JsError::new("ecdsa scalar sigma is invalid"))?,
+            }),
+            Value::EcdsaPublicKey(public_key) => {
+                let public_key: [u8; 33] =
+                    public_key.public_key.try_into().map_err(|_| JsError::new("ecdsa public key must be 33 bytes"))?;
+                CoreNadaValue::new_ecdsa_public_key::<EcdsaPublicKeyArray>(public_key.into())
+            }
+            Value::EddsaPrivateKeyShare(share) => {
+                CoreNadaValue::new_eddsa_private_key(Self::proto_to_private_key(share)?)
+            }
+            Value::EddsaMessage(message) => CoreNadaValue::new_eddsa_message(message.message),
+            Value::EddsaSignature(signature) => {
+                CoreNadaValue::new_eddsa_signature(signature::EddsaSignature::from_bytes(&signature.signature)?)
+            }
+            Value::EddsaPublicKey(public_key) => {
+                let public_key: [u8; 32] =
+                    public_key.public_key.try_into().map_err(|_| JsError::new("eddsa public key must be 32 bytes"))?;
+                CoreNadaValue::new_eddsa_public_key(public_key)
+            }
+            Value::StoreId(store_id) => {
+                let store_id: [u8; 16] =
+                    store_id.store_id.try_into().map_err(|_| JsError::new("store id must be 16 bytes"))?;
+                CoreNadaValue::new_store_id(store_id)
+            }
+            Value::Array(array) => {
+                if array.values.is_empty() {
+                    return Err(JsError::new("Empty arrays are not supported"));
+                }
+                let values = array
+                    .values
+                    .into_iter()
+                    .map(|value| Self::value_from_proto(value, modulo))
+                    .collect::<JsResult<Vec<_>>>()?;
+                CoreNadaValue::new_array_non_empty(values).map_err(|e| JsError::new(&format!("Invalid array: {e}")))?
+            }
+            Value::Tuple(tuple) => {
+                let left = tuple.left.ok_or_else(|| JsError::new("Failed tuple left not found"))?;
+                let right = tuple.right.ok_or_else(|| JsError::new("Failed tuple right not found"))?;
+                let left = Self::value_from_proto(*left, modulo)?;
+                let right = Self::value_from_proto(*right, modulo)?;
+                CoreNadaValue::new_tuple(left, right).map_err(|e| JsError::new(&format!("Invalid tuple: {e}")))?
+            }
+        };
+        Ok(nada_value)
+    }
+
+    fn proto_to_private_key<T: Curve>(share: proto::PrivateKeyShare) -> JsResult<ThresholdPrivateKeyShare<T>> {
+        let i = u16::try_from(share.i).map_err(|_| JsError::new("Invalid Ecdsa i"))?;
+        let x = non_zero_secret_scalar_from_bytes(&share.x)?;
+        let shared_public_key = non_zero_point_from_bytes(&share.shared_public_key)?;
+        let public_shares =
+            share.public_shares.iter().map(|share| non_zero_point_from_bytes(share)).collect::<Result<_, _>>()?;
+        Self::build_private_key(i, x, shared_public_key, public_shares, None)
+    }
 }
 
 fn non_zero_point_from_js_value<T: Curve>(js_value: JsValue) -> JsResult<NonZero<Point<T>>> {
-    let bytes = Uint8Array::from(js_value).to_vec();
-    let point =
-        Point::from_bytes(&bytes).map_err(|_| JsError::new("Invalid ecdsa private key point: invalid bytes"))?;
+    non_zero_point_from_bytes(&Uint8Array::from(js_value).to_vec())
+}
+
+fn non_zero_point_from_bytes<T: Curve>(bytes: &[u8]) -> JsResult<NonZero<Point<T>>> {
+    let point = Point::from_bytes(bytes).map_err(|_| JsError::new("Invalid ecdsa private key point: invalid bytes"))?;
     NonZero::from_point(point).ok_or(JsError::new("Invalid ecdsa private key point: point is zero"))
 }
 
+fn non_zero_scalar_from_js_value<T: Curve>(js_value: JsValue) -> JsResult<NonZero<Scalar<T>>> {
+    let scalar = Scalar::from_le_bytes(Uint8Array::from(js_value).to_vec())
+        .map_err(|_| JsError::new("Invalid key share index: invalid bytes"))?;
+    NonZero::from_scalar(scalar).ok_or(JsError::new("Invalid key share index: scalar is zero"))
+}
+
 fn non_zero_secret_scalar_from_js_value<T: Curve>(js_value: JsValue) -> JsResult<NonZero<SecretScalar<T>>> {
-    let bytes = Uint8Array::from(js_value).to_vec();
-    let scalar = SecretScalar::from_le_bytes(&bytes)
+    non_zero_secret_scalar_from_bytes(&Uint8Array::from(js_value).to_vec())
+}
+
+fn non_zero_secret_scalar_from_bytes<T: Curve>(bytes: &[u8]) -> JsResult<NonZero<SecretScalar<T>>> {
+    let scalar = SecretScalar::from_le_bytes(bytes)
         .map_err(|_| JsError::new("Invalid ecdsa private key secret scalar: invalid bytes"))?;
     NonZero::from_secret_scalar(scalar).ok_or(JsError::new("Invalid ecdsa private key secret scalar: scalar is zero"))
 }
@@ -1068,7 +2548,7 @@ mod test {
     fn secret_blob() {
         let contents = b"hi mom".to_vec();
         let secret = NadaValue::new_secret_blob(contents.clone());
-        assert_eq!(secret.to_byte_array().map_err(JsValue::from), Ok(contents));
+        assert_eq!(secret.to_byte_array().map(|bytes| bytes.to_vec()).map_err(JsValue::from), Ok(contents));
     }
 
     #[wasm_bindgen_test]
@@ -1125,6 +2605,60 @@ mod test {
         Ok(())
     }
 
+    #[wasm_bindgen_test]
+    fn robust_unmask() -> Result<(), JsValue> {
+        let parties: Vec<_> = (1..=4).map(|party| PartyId(vec![party])).collect();
+        let masker = SecretMasker::new_64_bit_safe_prime(1, parties)?;
+        let make_values = |integer: &str, blob: Vec<u8>| -> Result<NadaValues, JsValue> {
+            let mut values = NadaValues::new()?;
+            values.insert("secret_integer".into(), &NadaValue::new_secret_integer(integer)?);
+            values.insert("secret_blob".into(), &NadaValue::new_secret_blob(blob));
+            Ok(values)
+        };
+        let values = make_values("42", vec![1, 2, 3])?;
+        let shares = masker.mask(values.clone())?;
+        let other_shares = masker.mask(make_values("1337", vec![4, 5, 6])?)?;
+
+        // Replace the shares of some parties with shares of other values.
+        let corrupt = |faulty: &[u8]| -> Vec<PartyShares> {
+            shares
+                .iter()
+                .map(|party_shares| match other_shares.iter().find(|other| other.party.0 == party_shares.party.0) {
+                    Some(other) if faulty.contains(party_shares.party.0.first().unwrap_or(&0)) => other.clone(),
+                    _ => party_shares.clone(),
+                })
+                .collect()
+        };
+
+        let mut result = masker.unmask_robust(shares.clone())?;
+        assert!(result.faulty_parties().is_empty());
+        assert_eq!(result.take_values(), values);
+
+        let mut result = masker.unmask_robust(corrupt(&[3]))?;
+        let faulty: Vec<_> = result.faulty_parties().into_iter().map(|party| party.0).collect();
+        assert_eq!(faulty, vec![vec![3]]);
+        assert_eq!(result.take_values(), values);
+        assert_eq!(result.take_values().length(), 0);
+
+        assert!(masker.unmask_robust(corrupt(&[1, 3])).is_err());
+        let mut repeated = shares.clone();
+        repeated.extend(shares.first().cloned());
+        assert!(masker.unmask_robust(repeated).is_err());
+
+        let parties: Vec<_> = (1..=17).map(|party| PartyId(vec![party])).collect();
+        let masker = SecretMasker::new_64_bit_safe_prime(1, parties)?;
+        let shares = masker.mask(make_values("42", vec![1, 2, 3])?)?;
+        assert!(masker.unmask_robust(shares).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn subset_combinations() {
+        assert_eq!(combinations(&[1, 2, 3], 2), vec![vec![1, 2], vec![1, 3], vec![2, 3]]);
+        assert_eq!(combinations(&[1, 2], 0), vec![Vec::<i32>::new()]);
+        assert!(combinations(&[1, 2], 3).is_empty());
+    }
+
     #[wasm_bindgen_test]
     fn value_classification() -> Result<(), JsValue> {
         let mut values = NadaValues::new()?;
@@ -1192,4 +2726,380 @@ mod test {
         assert_eq!(values, unmasked_values);
         Ok(())
     }
+
+    fn compound_values() -> Result<NadaValues, JsValue> {
+        let mut fields = NadaValues::new()?;
+        fields.insert("a".into(), &NadaValue::new_secret_integer("1")?);
+        fields.insert("b".into(), &NadaValue::new_public_unsigned_integer("2")?);
+
+        let mut values = NadaValues::new()?;
+        values.insert(
+            "array".into(),
+            &NadaValue::new_array(vec![NadaValue::new_secret_integer("1")?, NadaValue::new_secret_integer("-2")?])?,
+        );
+        values.insert(
+            "tuple".into(),
+            &NadaValue::new_tuple(&NadaValue::new_secret_boolean(true)?, &NadaValue::new_public_integer("3")?)?,
+        );
+        values.insert(
+            "n_tuple".into(),
+            &NadaValue::new_n_tuple(vec![
+                NadaValue::new_secret_unsigned_integer("4")?,
+                NadaValue::new_secret_integer("-5")?,
+                NadaValue::new_public_boolean(false)?,
+            ])?,
+        );
+        values.insert("object".into(), &NadaValue::new_object(&fields)?);
+        Ok(values)
+    }
+
+    #[wasm_bindgen_test]
+    fn array_with_mixed_types() -> Result<(), JsValue> {
+        let values = vec![NadaValue::new_secret_integer("1")?, NadaValue::new_secret_boolean(true)?];
+        assert!(NadaValue::new_array(values).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn nested_blobs() -> Result<(), JsValue> {
+        let blob = || NadaValue::new_secret_blob(vec![1, 2, 3]);
+        assert!(NadaValue::new_array(vec![blob()]).is_err());
+        assert!(NadaValue::new_tuple(&blob(), &NadaValue::new_secret_integer("1")?).is_err());
+        assert!(NadaValue::new_n_tuple(vec![NadaValue::new_secret_integer("1")?, blob()]).is_err());
+        let mut fields = NadaValues::new()?;
+        fields.insert("blob".into(), &blob());
+        assert!(NadaValue::new_object(&fields).is_err());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn compound_mask_unmask() -> Result<(), JsValue> {
+        let values = compound_values()?;
+        let masker = make_masker();
+        let party_shares = masker.mask(values.clone())?;
+
+        let masked_values = party_shares.first().unwrap().shares.clone();
+        let js_object = masked_values.to_js_object()?;
+        let from_values = EncryptedNadaValues::from_js_object(&js_object, masker.modulo())?;
+        assert_eq!(masked_values, from_values);
+
+        let unmasked_values = masker.unmask(party_shares)?;
+        assert_eq!(values, unmasked_values);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn compound_to_record() -> Result<(), JsValue> {
+        let values = compound_values()?;
+        let record = values.to_record()?;
+        let array = js_sys::Reflect::get(&record, &"array".into())?;
+        assert_eq!(js_sys::Reflect::get(&array, &"type".into())?, "Array");
+
+        let elements = Array::from(&js_sys::Reflect::get(&array, &"value".into())?);
+        assert_eq!(elements.length(), 2);
+        let element = elements.get(1);
+        assert_eq!(js_sys::Reflect::get(&element, &"type".into())?, "SecretInteger");
+        assert_eq!(js_sys::Reflect::get(&element, &"value".into())?, "-2");
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn rational_mask_unmask() -> Result<(), JsValue> {
+        let masker = make_masker();
+        let mut values = NadaValues::new()?;
+        values.insert("secret".into(), &NadaValue::new_secret_rational("-12.345", 3, &masker.modulo())?);
+        values.insert("public".into(), &NadaValue::new_public_rational("0.5", 2, &masker.modulo())?);
+
+        let unmasked_values = masker.unmask(masker.mask(values)?)?;
+        let secret = NadaValue(unmasked_values.0.get("secret").unwrap().clone());
+        assert_eq!(secret.to_integer().map_err(JsValue::from), Ok("-12345".to_string()));
+        assert_eq!(secret.to_rational(3).map_err(JsValue::from), Ok("-12.345".to_string()));
+        let public = NadaValue(unmasked_values.0.get("public").unwrap().clone());
+        assert_eq!(public.to_rational(2).map_err(JsValue::from), Ok("0.50".to_string()));
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn rational_bounds() -> Result<(), JsValue> {
+        // The largest magnitude the 64 bit safe prime can encode is (p-1)/2.
+        let modulo = make_masker().modulo();
+        for value in ["9223372036318953473", "-9223372036318953473", "4611686018427387904"] {
+            assert_eq!(NadaValue::new_secret_rational(value, 0, &modulo)?.to_integer()?, value);
+        }
+        let value = NadaValue::new_public_rational("922337203631895347.3", 1, &modulo)?;
+        assert_eq!(value.to_rational(1)?, "922337203631895347.3");
+
+        let parties = vec![PartyId(vec![1]), PartyId(vec![2]), PartyId(vec![3])];
+        for masker in [
+            SecretMasker::new_64_bit_safe_prime(1, parties.clone())?,
+            SecretMasker::new_128_bit_safe_prime(1, parties.clone())?,
+            SecretMasker::new_256_bit_safe_prime(1, parties)?,
+        ] {
+            assert_ne!(masker.modulo().signed_max(), BigUint::default());
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn invalid_rationals() {
+        let modulo = make_masker().modulo();
+        assert!(NadaValue::new_secret_rational("1.2345", 3, &modulo).is_err());
+        assert!(NadaValue::new_secret_rational("1,5", 1, &modulo).is_err());
+        assert!(NadaValue::new_secret_rational("-", 1, &modulo).is_err());
+        assert!(NadaValue::new_secret_rational("9223372036318953474", 0, &modulo).is_err());
+        assert!(NadaValue::new_secret_rational("-922337203631895347.4", 1, &modulo).is_err());
+        assert!(NadaValue::new_secret_rational("4611686018427387.903", 3, &modulo).is_ok());
+        assert!(NadaValue::new_secret_rational("0", 21, &modulo).is_err());
+        assert!(NadaValue::new_secret_rational("0", u32::MAX, &modulo).is_err());
+        assert!(NadaValue::new_secret_integer("1").is_ok_and(|value| value.to_rational(u32::MAX).is_err()));
+    }
+
+    #[wasm_bindgen_test]
+    fn record_round_trip() -> Result<(), JsValue> {
+        let mut values = compound_values()?;
+        values.insert("integer".into(), &NadaValue::new_public_integer("-42")?);
+        values.insert("unsigned_integer".into(), &NadaValue::new_public_unsigned_integer("42")?);
+        values.insert("boolean".into(), &NadaValue::new_public_boolean(true)?);
+        values.insert("secret_boolean".into(), &NadaValue::new_secret_boolean(false)?);
+        values.insert("ecdsa_private_key".into(), &NadaValue::new_ecdsa_private_key(vec![1; 32])?);
+        values.insert("ecdsa_message".into(), &NadaValue::new_ecdsa_digest_message(vec![1; 32])?);
+        values.insert("ecdsa_public_key".into(), &NadaValue::new_ecdsa_public_key(vec![1; 33])?);
+        values.insert("ecdsa_signature".into(), &NadaValue::new_ecdsa_signature(vec![1; 32], vec![2; 32])?);
+        values.insert("eddsa_message".into(), &NadaValue::new_eddsa_message(vec![1; 32])?);
+        values.insert("eddsa_public_key".into(), &NadaValue::new_eddsa_public_key(vec![1; 32])?);
+        values.insert("store_id".into(), &NadaValue::new_store_id(vec![1; 16])?);
+
+        let record = values.to_record()?;
+        let from_values = NadaValues::from_record(&record)?;
+        assert_eq!(values, from_values);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn record_error_names_key() -> Result<(), JsValue> {
+        let mut values = compound_values()?;
+        values.insert("valid".into(), &NadaValue::new_secret_integer("1")?);
+        let record = values.to_record()?;
+        let array = js_sys::Reflect::get(&record, &"array".into())?;
+        let element = Array::from(&js_sys::Reflect::get(&array, &"value".into())?).get(1);
+        js_sys::Reflect::set(&element, &"value".into(), &"not a number".into())?;
+
+        let error = NadaValues::from_record(&record).map_err(JsValue::from).unwrap_err();
+        let message = String::from(error.unchecked_into::<js_sys::Error>().message());
+        assert!(message.starts_with("ValueError: array[1]: Invalid integer value"), "{message}");
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn collection_access() -> Result<(), JsValue> {
+        let mut values = NadaValues::new()?;
+        values.insert("b".into(), &NadaValue::new_secret_integer("42")?);
+        values.insert("a".into(), &NadaValue::new_public_integer("1")?);
+
+        assert!(values.has("a"));
+        assert_eq!(values.get("b"), Some(NadaValue::new_secret_integer("42")?));
+        assert_eq!(values.keys(), vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(values.entries().length(), 2);
+
+        assert!(values.remove("a").is_some());
+        assert!(values.remove("a").is_none());
+        assert!(!values.has("a"));
+        assert_eq!(values.length(), 1);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn merge() -> Result<(), JsValue> {
+        let mut values = NadaValues::new()?;
+        values.insert("a".into(), &NadaValue::new_public_integer("1")?);
+        let mut other = NadaValues::new()?;
+        other.insert("a".into(), &NadaValue::new_public_integer("2")?);
+        other.insert("b".into(), &NadaValue::new_public_integer("3")?);
+
+        assert!(values.clone().merge(&other, MergePolicy::Error).is_err());
+
+        let mut kept = values.clone();
+        kept.merge(&other, MergePolicy::KeepExisting)?;
+        assert_eq!(kept.get("a"), Some(NadaValue::new_public_integer("1")?));
+        assert_eq!(kept.length(), 2);
+
+        values.merge(&other, MergePolicy::Overwrite)?;
+        assert_eq!(values.get("a"), Some(NadaValue::new_public_integer("2")?));
+        assert_eq!(values.length(), 2);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn partition() -> Result<(), JsValue> {
+        let mut values = NadaValues::new()?;
+        values.insert("a".into(), &NadaValue::new_secret_integer("42")?);
+        values.insert("b".into(), &NadaValue::new_secret_blob(vec![1, 2, 3]));
+        values.insert("c".into(), &NadaValue::new_public_integer("101")?);
+
+        let mut partition = values.partition(vec!["PublicInteger".into()])?;
+        let secret = partition.pop().unwrap();
+        let public = partition.pop().unwrap();
+        assert_eq!(public.keys(), vec!["c".to_string()]);
+        assert_eq!(secret.keys(), vec!["a".to_string(), "b".to_string()]);
+
+        let masker = make_masker();
+        let shares = masker.mask(values)?.pop().unwrap().shares;
+        let mut integers = shares.filter(vec!["ShamirShareInteger".into(), "Integer".into()])?;
+        assert_eq!(integers.keys(), vec!["a".to_string(), "c".to_string()]);
+
+        let removed = integers.remove("a")?.ok_or("value not removed")?;
+        assert_eq!(js_sys::Reflect::get(&removed, &JsValue::from("type"))?, "ShamirShareInteger");
+        assert!(integers.remove("a")?.is_none());
+        assert_eq!(integers.keys(), vec!["c".to_string()]);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn encrypted_nada_values_proto_round_trip() -> Result<(), JsValue> {
+        let mut values = NadaValues::new()?;
+        values.insert("integer".into(), &NadaValue::new_public_integer("42")?);
+        values.insert("secret_integer".into(), &NadaValue::new_secret_integer("42")?);
+        values.insert("secret_boolean".into(), &NadaValue::new_secret_boolean(true)?);
+        values.insert("secret_blob".into(), &NadaValue::new_secret_blob(vec![1, 2, 3]));
+        values.insert("ecdsa_private_key".into(), &NadaValue::new_ecdsa_private_key(vec![1; 32])?);
+        values.insert("ecdsa_message".into(), &NadaValue::new_ecdsa_digest_message(vec![1; 32])?);
+        values.insert("ecdsa_signature".into(), &NadaValue::new_ecdsa_signature(vec![1; 32], vec![1; 32])?);
+        values.insert("eddsa_public_key".into(), &NadaValue::new_eddsa_public_key(vec![1; 32])?);
+        values.insert("store_id".into(), &NadaValue::new_store_id(vec![1; 16])?);
+        values.insert(
+            "array".into(),
+            &NadaValue::new_array(vec![NadaValue::new_secret_integer("1")?, NadaValue::new_secret_integer("2")?])?,
+        );
+        values.insert(
+            "tuple".into(),
+            &NadaValue::new_tuple(&NadaValue::new_secret_boolean(true)?, &NadaValue::new_public_integer("3")?)?,
+        );
+
+        let masker = make_masker();
+        let party_shares = masker.mask(values)?;
+        for party_shares in party_shares {
+            let encoded = party_shares.shares.to_proto_values()?;
+            assert_eq!(encoded.length() as usize, party_shares.shares.length());
+            let from_values = EncryptedNadaValues::from_proto_values(&encoded, masker.modulo())?;
+            assert_eq!(party_shares.shares, from_values);
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn proto_size() -> Result<(), JsValue> {
+        let mut values = NadaValues::new()?;
+        values.insert("id".into(), &NadaValue::new_store_id(vec![1; 16])?);
+        for party_shares in make_masker().mask(values)? {
+            // The name, then the value's tag and length followed by the store id message's tag, length and bytes.
+            assert_eq!(party_shares.shares.proto_size()?, 2 + 2 + 2 + 16);
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn unsupported_compounds_proto() -> Result<(), JsValue> {
+        let masker = make_masker();
+        for name in ["n_tuple", "object"] {
+            let mut values = NadaValues::new()?;
+            values.insert(name.into(), &compound_values()?.get(name).ok_or("missing value")?);
+            for party_shares in masker.mask(values)? {
+                assert!(party_shares.shares.to_proto_values().is_err());
+                assert!(party_shares.shares.proto_size().is_err());
+            }
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn empty_array_proto() {
+        let array = proto::Array { values: Vec::new(), inner_type: None };
+        let value = proto::Value { value: Some(proto::value::Value::Array(array)) };
+        let bytes = proto::NamedValue { name: "array".into(), value: Some(value) }.encode_to_vec();
+        let values = Array::of1(&Uint8Array::from(bytes.as_slice()));
+        assert!(EncryptedNadaValues::from_proto_values(&values, make_masker().modulo()).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn digest_messages() -> Result<(), JsValue> {
+        let hex = |value: NadaValue| -> Result<String, JsValue> {
+            Ok(value.to_byte_array()?.to_vec().iter().map(|byte| format!("{byte:02x}")).collect())
+        };
+        assert_eq!(
+            hex(NadaValue::new_sha256_digest_message(b"abc"))?,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hex(NadaValue::new_keccak256_digest_message(b""))?,
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            hex(NadaValue::new_eip191_digest_message(b"hello world"))?,
+            "d9eba16ed0ecae432b71fe008c98cc872bb4cc214d3220a36f365326cf807d68"
+        );
+        assert_eq!(NadaValue::new_sha256_digest_message(b"abc").type_name()?, "EcdsaDigestMessage");
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn wipe() -> Result<(), JsValue> {
+        let mut blob = NadaValue::new_secret_blob(b"hi mom".to_vec());
+        blob.wipe();
+        assert_eq!(blob.to_byte_array()?.to_vec(), vec![0; 6]);
+
+        let mut integer = NadaValue::new_secret_integer("42")?;
+        integer.wipe();
+        assert_eq!(integer.to_integer()?, "0");
+
+        let mut private_key = NadaValue::new_ecdsa_private_key(vec![7; 32])?;
+        private_key.wipe();
+        assert_eq!(private_key.to_byte_array()?.to_vec(), vec![0; 32]);
+
+        let mut values = NadaValues::new()?;
+        values.insert("array".to_string(), &NadaValue::new_array(vec![NadaValue::new_secret_integer("1")?])?);
+        values.wipe();
+        assert_eq!(values.length(), 0);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn symbol_hooks() -> Result<(), JsValue> {
+        install_symbol_hooks()?;
+        let symbol = js_sys::Symbol::for_("nodejs.util.inspect.custom");
+        let value = JsValue::from(NadaValue::new_secret_integer("42")?);
+        let hook: js_sys::Function = js_sys::Reflect::get(&value, &symbol)?.dyn_into()?;
+        assert_eq!(hook.call0(&value)?, "SecretInteger(redacted)");
+
+        let mut values = NadaValues::new()?;
+        values.insert("b".into(), &NadaValue::new_secret_integer("42")?);
+        values.insert("a".into(), &NadaValue::new_public_integer("1")?);
+        let entries = Array::from(&JsValue::from(values));
+        assert_eq!(entries.length(), 2);
+        assert_eq!(Array::from(&entries.get(0)).get(0), "a");
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn redacted() -> Result<(), JsValue> {
+        let secret = NadaValue::new_secret_integer("42")?;
+        assert_eq!(secret.to_redacted_string()?, "SecretInteger(redacted)");
+        let record = secret.to_json()?;
+        assert_eq!(js_sys::Reflect::get(&record, &JsValue::from("type"))?, "SecretInteger");
+        assert!(!js_sys::Reflect::has(&record, &JsValue::from("value"))?);
+
+        let revealed = secret.reveal()?;
+        assert_eq!(js_sys::Reflect::get(&revealed, &JsValue::from("value"))?, "42");
+
+        let mut values = NadaValues::new()?;
+        values.insert("foo".to_string(), &secret);
+        values.insert("bar".to_string(), &NadaValue::new_secret_blob(b"hi mom".to_vec()));
+        assert_eq!(
+            values.to_redacted_string()?,
+            "NadaValues { bar: SecretBlob(redacted, size 6), foo: SecretInteger(redacted) }"
+        );
+        let record = values.to_json()?;
+        let bar = js_sys::Reflect::get(&record, &JsValue::from("bar"))?;
+        assert_eq!(js_sys::Reflect::get(&bar, &JsValue::from("size"))?, JsValue::from(6));
+        Ok(())
+    }
 }